@@ -0,0 +1,131 @@
+//! Ingestion of a live or replayed ADS-B position feed, so `Aircraft`/`Flight` state transitions
+//! can be driven by observed movements instead of purely the `Dispatcher`'s simulated
+//! takeoff/landing. A "live/replay" run feeds every flight it has positions for through here
+//! first; only legs the feed never observed are left for the dispatcher to simulate normally.
+//!
+//! Feeds arrive as newline-delimited JSON, the shape typical ADS-B aggregators emit: one record
+//! per observed position, `{hex, flight, lat, lon, altitude, timestamp}`. `lat`/`lon` are carried
+//! through for parsing fidelity but unused here, since neither `Aircraft` nor `Flight` tracks
+//! position beyond "on the ground at X" / "in flight".
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::aircraft::{FlightId, Location};
+use crate::model::Model;
+
+/// A single observed position report.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdsbPositionRecord {
+    /// The aircraft's 24-bit ICAO hex address, e.g. `"a1b2c3"`.
+    pub hex: String,
+    /// Callsign/flight number as broadcast, if the transponder is sending one. Unused for now:
+    /// tails are resolved via `TailRegistry` and flights via `AdsbFeedDriver::flight_for_tail`,
+    /// since a scenario's `Flight::flight_number` isn't guaranteed to match a live callsign.
+    pub flight: Option<String>,
+    pub lat: f64,
+    pub lon: f64,
+    /// Feet above ground/sea level, however the feed reports it.
+    pub altitude: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Below this altitude (feet), a position report is treated as "on the ground"; at or above it,
+/// the aircraft is airborne. ADS-B feeds report altitude continuously, not as a boolean, so a
+/// single threshold is what turns a stream of positions into discrete takeoff/landing events.
+const AIRBORNE_ALTITUDE_FEET: f64 = 50.0;
+
+/// Maps an observed ICAO hex address to the tail number `Model::fleet` uses for the same
+/// aircraft, standing in for a registration lookup against a real aircraft database.
+pub type TailRegistry = HashMap<String, String>;
+
+/// Replays (or live-tails) a feed of `AdsbPositionRecord`s against a `Model`, realizing
+/// `Aircraft::takeoff`/`land` and `Flight::takeoff`/`land` calls as tails cross the airborne
+/// threshold.
+#[derive(Default)]
+pub struct AdsbFeedDriver {
+    registry: TailRegistry,
+    /// Whether each tail was airborne as of its last observed position, so a new report can be
+    /// classified as a takeoff, a landing, or neither.
+    airborne: HashMap<String, bool>,
+}
+
+impl AdsbFeedDriver {
+    pub fn new(registry: TailRegistry) -> Self {
+        Self {
+            registry,
+            airborne: HashMap::new(),
+        }
+    }
+
+    /// Parse `feed` as newline-delimited JSON `AdsbPositionRecord`s and apply each to `model` in
+    /// order.
+    pub fn ingest_str(&mut self, model: &Model, feed: &str) -> Result<(), Box<dyn Error>> {
+        for line in feed.lines().filter(|line| !line.trim().is_empty()) {
+            let record: AdsbPositionRecord = serde_json::from_str(line)?;
+            self.apply(model, &record);
+        }
+        Ok(())
+    }
+
+    /// Apply a single observed position. A tail with no `registry` entry, or one whose observed
+    /// altitude doesn't cross `AIRBORNE_ALTITUDE_FEET` relative to its last known state, is a
+    /// no-op; a tail with no currently active flight is skipped since there's nothing to realize
+    /// the movement onto.
+    pub fn apply(&mut self, model: &Model, record: &AdsbPositionRecord) {
+        let Some(tail) = self.registry.get(&record.hex) else {
+            return;
+        };
+        let was_airborne = self.airborne.get(tail).copied().unwrap_or(false);
+        let is_airborne = record.altitude >= AIRBORNE_ALTITUDE_FEET;
+        self.airborne.insert(tail.clone(), is_airborne);
+        if is_airborne == was_airborne {
+            return;
+        }
+
+        let Some(aircraft_lock) = model.fleet.get(tail) else {
+            return;
+        };
+        let Some(flight_id) = Self::flight_for_tail(model, tail) else {
+            return;
+        };
+
+        if is_airborne {
+            let mut flight = model.flight_write(flight_id);
+            if !flight.took_off() {
+                flight.takeoff(record.timestamp);
+            }
+            drop(flight);
+            let mut aircraft = aircraft_lock.write().unwrap();
+            if matches!(aircraft.location, Location::Ground(..)) {
+                aircraft.takeoff(flight_id, record.timestamp);
+            }
+        } else {
+            let mut flight = model.flight_write(flight_id);
+            if flight.arrive_time.is_none() {
+                flight.land(record.timestamp);
+            }
+            let dest = flight.dest;
+            drop(flight);
+            let mut aircraft = aircraft_lock.write().unwrap();
+            if matches!(aircraft.location, Location::InFlight(id) if id == flight_id) {
+                aircraft.land(dest, record.timestamp);
+            }
+        }
+    }
+
+    /// The not-yet-arrived flight currently assigned to `tail`, i.e. the leg a new position
+    /// report for that tail should be attributed to.
+    fn flight_for_tail(model: &Model, tail: &str) -> Option<FlightId> {
+        model.flights.values().find_map(|f| {
+            let flt = f.try_read().ok()?;
+            (flt.aircraft_tail.as_deref() == Some(tail)
+                && !flt.cancelled
+                && flt.arrive_time.is_none())
+            .then_some(flt.id)
+        })
+    }
+}
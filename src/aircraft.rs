@@ -4,6 +4,7 @@ use crate::crew::CrewId;
 use crate::model::Model;
 use crate::{airport::*, metrics::ModelEventType};
 use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
 
 pub type FlightId = u64;
 
@@ -11,7 +12,7 @@ pub type FlightId = u64;
 /// If `arrive_time` is None, then this must be a flight in progress.
 ///
 /// Owner: Model
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Flight {
     pub id: FlightId,
     pub flight_number: String,
@@ -94,19 +95,23 @@ impl Flight {
 }
 
 /// Owner: Aircraft or Crew
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Location {
     /// On the ground at airport `self.0` since time `self.1`.
     Ground(AirportCode, DateTime<Utc>),
     InFlight(FlightId),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Aircraft {
     pub tail: String,
     pub location: Location,
     /// (Name, passenger capacity)
     pub type_: (String, u16),
+    /// Belly cargo/bag weight this type can carry on top of its passenger seats, in the same
+    /// units as `PassengerDemand::bag_weight`. Forms the second dimension of the `Capacity`
+    /// passed to `Airport::mark_departure`/`deduct_passengers`.
+    pub cargo_capacity: u32,
     pub next_claimed: Option<FlightId>,
 }
 
@@ -117,11 +122,13 @@ impl Aircraft {
         now: &DateTime<Utc>,
         typename: String,
         capacity: u16,
+        cargo_capacity: u32,
     ) -> Self {
         Aircraft {
             tail,
             location: Location::Ground(location, *now - TimeDelta::hours(2)),
             type_: (typename, capacity),
+            cargo_capacity,
             next_claimed: None,
         }
     }
@@ -1,6 +1,7 @@
 use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
 use std::{
-    cmp::{min, Ordering},
+    cmp::Ordering,
     collections::{HashMap, HashSet},
     fmt::Debug,
     iter::{empty, repeat, repeat_with, Repeat},
@@ -11,9 +12,10 @@ use crate::{
     aircraft::{Flight, FlightId},
     crew::CrewId,
     model::Model,
+    slot_clock::SlotClock,
 };
 
-#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd)]
+#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
 pub struct AirportCode {
     letters: [u8; 3],
 }
@@ -43,7 +45,62 @@ impl std::fmt::Display for AirportCode {
     }
 }
 
-#[derive(Debug)]
+/// Per-dimension capacity room available for boarding, and the per-passenger requirement
+/// against it: `[seats, belly cargo/bag weight]`. A demand only boards when every dimension
+/// still has room (see `deduct_passengers`), mirroring how a vehicle-routing capacity
+/// constraint validates a multi-dimensional load against a vehicle's multi-dimensional capacity.
+pub type Capacity = [u32; 2];
+
+/// Continuously-refilling token bucket, adapted from Garage's `tranquilizer` pacing idea: rather
+/// than a fixed hourly window that lets every flight held back by it through in one burst the
+/// moment the window rolls over, capacity drips back in continuously at `capacity` tokens per
+/// hour, so concurrent demand past the limit gets spread out instead of queuing up for a cliff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenBucket {
+    pub capacity: u32,
+    last_refill: DateTime<Utc>,
+    tokens: f64,
+}
+
+impl TokenBucket {
+    pub fn new(capacity: u32, now: DateTime<Utc>) -> Self {
+        Self {
+            capacity,
+            last_refill: now,
+            tokens: capacity as f64,
+        }
+    }
+
+    fn refilled(&self, now: DateTime<Utc>) -> f64 {
+        let elapsed_hours =
+            (now - self.last_refill).num_milliseconds().max(0) as f64 / 3_600_000.0;
+        (self.tokens + elapsed_hours * self.capacity as f64).min(self.capacity as f64)
+    }
+
+    /// The earliest time at or after `now` that a token will be available, without mutating the
+    /// bucket. Returns `now` itself if one is already available.
+    pub fn next_available(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        if self.capacity == 0 {
+            // No capacity ever refills; don't pretend there's a next slot.
+            return now + TimeDelta::weeks(52 * 100);
+        }
+        let tokens = self.refilled(now);
+        if tokens >= 1.0 {
+            return now;
+        }
+        let hours_needed = (1.0 - tokens) / self.capacity as f64;
+        now + TimeDelta::milliseconds((hours_needed * 3_600_000.0).ceil() as i64)
+    }
+
+    /// Refill up to `now`, then spend one token. Call only once the caller has confirmed (e.g.
+    /// via `next_available`) that a token is available as of `now`.
+    pub fn consume(&mut self, now: DateTime<Utc>) {
+        self.tokens = (self.refilled(now) - 1.0).max(0.0);
+        self.last_refill = now;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Airport {
     pub code: AirportCode,
     pub fleet: HashSet<String>,
@@ -53,56 +110,38 @@ pub struct Airport {
 
     pub max_dep_per_hour: u32,
     pub max_arr_per_hour: u32,
-    pub departure_count: (DateTime<Utc>, u32),
-    pub arrival_count: (DateTime<Utc>, u32),
+    pub departure_bucket: TokenBucket,
+    pub arrival_bucket: TokenBucket,
 }
 
 impl Airport {
     pub fn depart_time(&self, time: DateTime<Utc>) -> DateTime<Utc> {
-        if time - self.departure_count.0 >= TimeDelta::hours(1) {
-            // Seems like we need to reset the counter
-            time
-        } else if self.departure_count.1 < self.max_dep_per_hour {
-            // We can fit it in
-            time
-        } else {
-            // Delayed to the next slot
-            self.departure_count.0 + TimeDelta::minutes(60)
-        }
+        self.departure_bucket.next_available(time)
     }
 
     /// Precondition: The given flight has been assigned to an aircraft
-    pub fn mark_departure(&mut self, time: DateTime<Utc>, flight: &mut Flight, capacity: u16) {
-        if time - self.departure_count.0 >= TimeDelta::hours(1) {
-            self.departure_count = (time, 1);
-        } else {
-            self.departure_count.1 += 1;
-        }
+    pub fn mark_departure(&mut self, time: DateTime<Utc>, flight: &mut Flight, capacity: Capacity, model: &Model) {
+        self.departure_bucket.consume(time);
         debug_assert!(self.fleet.remove(flight.aircraft_tail.as_ref().unwrap()));
         self.crew.retain(|c| !flight.crew.contains(c));
-        self.deduct_passengers(flight.id, flight.dest, capacity, &mut flight.passengers);
+        let arrive_time = flight.est_arrive_time(&time);
+        self.deduct_passengers(
+            flight.id,
+            flight.dest,
+            capacity,
+            &mut flight.passengers,
+            arrive_time,
+            model,
+        );
     }
 
     // TODO reduce duplication
     pub fn arrive_time(&self, time: DateTime<Utc>) -> DateTime<Utc> {
-        if time - self.arrival_count.0 >= TimeDelta::hours(1) {
-            // Seems like we need to reset the counter
-            time
-        } else if self.arrival_count.1 < self.max_arr_per_hour {
-            // We can fit it in
-            time
-        } else {
-            // Delayed to the next slot
-            self.arrival_count.0 + TimeDelta::minutes(60)
-        }
+        self.arrival_bucket.next_available(time)
     }
 
     pub fn mark_arrival(&mut self, time: DateTime<Utc>, flight: &Flight) {
-        if time - self.arrival_count.0 >= TimeDelta::hours(1) {
-            self.arrival_count = (time, 1);
-        } else {
-            self.arrival_count.1 += 1;
-        }
+        self.arrival_bucket.consume(time);
         self.fleet.insert(flight.aircraft_tail.clone().unwrap());
         self.crew.extend(flight.crew.iter());
         self.accept_passengers(&flight.passengers);
@@ -112,24 +151,41 @@ impl Airport {
         &mut self,
         flight: FlightId,
         dest: AirportCode,
-        capacity: u16,
+        capacity: Capacity,
         onboard: &mut Vec<PassengerDemand>,
+        arrive_time: DateTime<Utc>,
+        model: &Model,
     ) {
-        let mut capacity = capacity as i32;
-        // TODO figure out which ones to prioritize
-        for demand in &mut self.passengers {
-            if capacity <= 0 {
+        let mut remaining = capacity;
+        let mut eligible: Vec<usize> = self
+            .passengers
+            .iter()
+            .enumerate()
+            .filter(|(_, demand)| demand.next_dest(self.code) == Some(dest))
+            .map(|(i, _)| i)
+            .collect();
+        eligible.sort_by_key(|&i| {
+            boarding_priority(&self.passengers[i], flight, dest, arrive_time, model)
+        });
+
+        for i in eligible {
+            if remaining.iter().all(|room| *room == 0) {
                 break;
             }
-            if demand.next_dest(self.code) != Some(dest) {
+            let demand = &mut self.passengers[i];
+            let requirement = demand.requirement();
+            let taking = std::iter::zip(remaining, requirement)
+                .map(|(room, unit)| if unit == 0 { u32::MAX } else { room / unit })
+                .min()
+                .unwrap_or(0)
+                .min(demand.count);
+            if taking == 0 {
                 continue;
             }
-            let taking = min(demand.count, capacity as u32);
-            // if taking == 0 {
-            //     println!("{} {}", demand.count, capacity);
-            // }
+            for (room, unit) in remaining.iter_mut().zip(requirement) {
+                *room -= unit * taking;
+            }
             onboard.push(demand.split_off(taking, flight));
-            capacity -= taking as i32;
         }
         self.passengers.retain(|demand| demand.count > 0);
     }
@@ -144,11 +200,14 @@ impl Airport {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PassengerDemand {
     pub path: Vec<AirportCode>,
     pub count: u32,
     pub flights_taken: Vec<FlightId>,
+    /// Checked bag weight this group carries per passenger, matching `Aircraft::cargo_capacity`'s
+    /// units. The second dimension of `requirement()`.
+    pub bag_weight: u32,
 }
 
 impl PassengerDemand {
@@ -172,8 +231,48 @@ impl PassengerDemand {
                 copy.push(flight);
                 copy
             },
+            bag_weight: self.bag_weight,
         }
     }
+
+    /// Per-dimension capacity this group consumes for each passenger boarded, matching
+    /// `Capacity`'s dimensions: one seat, plus `bag_weight` of belly cargo room.
+    fn requirement(&self) -> Capacity {
+        [1, self.bag_weight]
+    }
+}
+
+/// Sort key for boarding order in `deduct_passengers`, ascending (smallest sorts first):
+/// a demand still connecting onward from `dest` boards before one whose itinerary ends there,
+/// since stranding a connecting passenger is worse than delaying one who's already arrived.
+/// Among connecting demands, the tightest gap to their next flight's departure boards first,
+/// then (as a tiebreak, and for demands whose connection we can't find) the one with the most
+/// remaining legs.
+fn boarding_priority(
+    demand: &PassengerDemand,
+    flight: FlightId,
+    dest: AirportCode,
+    arrive_time: DateTime<Utc>,
+    model: &Model,
+) -> (bool, i64, i64) {
+    let remaining_legs = demand.path.iter().rev().take_while(|code| **code != dest).count();
+    let Some(next_leg_dest) = demand.next_dest(dest) else {
+        return (true, 0, -(remaining_legs as i64));
+    };
+    let slack = model
+        .flights
+        .iter()
+        // Compare keys before locking, not `other.id` after: `flight` may already be held under a
+        // write lock by the caller (`Model::depart_flight`), and `RwLock` is not reentrant.
+        .filter(|&(&id, _)| id != flight)
+        .filter_map(|(_, other)| {
+            let other = other.read().unwrap();
+            (!other.cancelled && other.origin == dest && other.dest == next_leg_dest)
+                .then(|| (other.sched_depart + other.dep_delay - arrive_time).num_minutes())
+        })
+        .min()
+        .unwrap_or(i64::MAX);
+    (false, slack, -(remaining_legs as i64))
 }
 
 // MARK: Disruptions
@@ -237,6 +336,52 @@ impl PartialOrd for Clearance {
     }
 }
 
+/// Serializable configuration for a `Disruption`, used by `Model::snapshot`/`Model::restore`.
+/// Trait objects can't be (de)serialized generically, so each concrete `Disruption` maps
+/// itself to one of these variants instead of its live slot-occupancy state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DisruptionRecord {
+    GroundDelayProgram {
+        site: AirportCode,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        hourly_rate: u16,
+        reason: Option<String>,
+        mode: GdpMode,
+    },
+    DepartureRateLimit {
+        site: AirportCode,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        hourly_rate: u16,
+        reason: Option<String>,
+    },
+}
+
+impl DisruptionRecord {
+    /// Rebuild a fresh, unoccupied `Disruption` from its recorded configuration.
+    pub fn restore(self) -> Arc<RwLock<dyn Disruption>> {
+        match self {
+            DisruptionRecord::GroundDelayProgram { site, start, end, hourly_rate, reason, mode } => {
+                Arc::new(RwLock::new(GroundDelayProgram {
+                    site,
+                    slots: SlotManager::new(start, end, hourly_rate),
+                    reason,
+                    mode,
+                    rbs_queue: RwLock::new(Vec::new()),
+                }))
+            }
+            DisruptionRecord::DepartureRateLimit { site, start, end, hourly_rate, reason } => {
+                Arc::new(RwLock::new(DepartureRateLimit {
+                    site,
+                    slots: SlotManager::new(start, end, hourly_rate),
+                    reason,
+                }))
+            }
+        }
+    }
+}
+
 pub trait Disruption: std::fmt::Debug + Send + Sync {
     /// By design, we should call this AFTER ensuring that all the resources are present for the flight
     /// (aircraft, crew, passengers)
@@ -257,6 +402,12 @@ pub trait Disruption: std::fmt::Debug + Send + Sync {
 
     fn departure_airports_affected(&self) -> Vec<AirportCode>;
     fn arrival_airports_affected(&self) -> Vec<AirportCode>;
+
+    /// Capture this disruption's configuration (site, window, rate, reason) for
+    /// `Model::snapshot`. Deliberately excludes which slots are currently occupied: that is
+    /// solver state, not ground truth, and is rebuilt by re-running every outstanding
+    /// flight's clearance request through `Model::reserve_earliest` after `Model::restore`.
+    fn snapshot(&self) -> DisruptionRecord;
 }
 
 #[derive(Debug)]
@@ -345,11 +496,171 @@ impl<T: PartialEq + Debug> SlotManager<T> {
     }
 }
 
+/// The result of probing a single `SlotHistory` index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Beyond the highest index ever marked: not ruled out, just not decided yet.
+    Future,
+    /// `SlotHistory::MAX_ENTRIES` or more behind the highest marked index, so it fell off the
+    /// back of the ring; whether it was ever taken is no longer tracked.
+    TooOld,
+    /// Marked taken.
+    Found,
+    /// Within the tracked window, but not marked.
+    NotFound,
+}
+
+/// A compact, fixed-capacity bitvector recording which discrete slot indices have been taken,
+/// addressed as a ring buffer so a long-running program doesn't need unbounded storage. Indices
+/// are expected to be non-decreasing over the life of the structure (the caller's time only
+/// moves forward); `mark` relies on that to know which stale bits to clear as the ring advances.
+#[derive(Debug)]
+pub struct SlotHistory {
+    bits: Vec<u64>,
+    /// Highest index ever marked, or `None` if nothing has been marked yet.
+    head: Option<usize>,
+}
+
+impl SlotHistory {
+    /// Ring capacity in slots. An index this far or farther behind `head` is `TooOld`.
+    pub const MAX_ENTRIES: usize = 1440;
+
+    pub fn new() -> Self {
+        Self {
+            bits: vec![0u64; Self::MAX_ENTRIES.div_ceil(64)],
+            head: None,
+        }
+    }
+
+    pub fn check(&self, slot_index: usize) -> Check {
+        let Some(head) = self.head else {
+            return Check::Future;
+        };
+        if slot_index > head {
+            Check::Future
+        } else if head - slot_index >= Self::MAX_ENTRIES {
+            Check::TooOld
+        } else if self.bit(slot_index) {
+            Check::Found
+        } else {
+            Check::NotFound
+        }
+    }
+
+    pub fn is_taken(&self, slot_index: usize) -> bool {
+        self.check(slot_index) == Check::Found
+    }
+
+    /// Mark `slot_index` as taken. If it's more than `MAX_ENTRIES` past the current head, the
+    /// whole ring is stale relative to it, so every bit is cleared first; otherwise only the
+    /// bits for the indices newly entering the ring (between the old head and this one) are
+    /// cleared, so the reused storage doesn't retain stale marks from long ago.
+    pub fn mark(&mut self, slot_index: usize) {
+        match self.head {
+            None => {
+                self.set_bit(slot_index, true);
+                self.head = Some(slot_index);
+            }
+            Some(head) if slot_index <= head => {
+                self.set_bit(slot_index, true);
+            }
+            Some(head) => {
+                if slot_index - head > Self::MAX_ENTRIES {
+                    self.bits.fill(0);
+                } else {
+                    for stale in (head + 1)..slot_index {
+                        self.set_bit(stale, false);
+                    }
+                }
+                self.set_bit(slot_index, true);
+                self.head = Some(slot_index);
+            }
+        }
+    }
+
+    /// Clear a previously-marked index, e.g. when its reservation is released, reaped, or moved
+    /// elsewhere by `compress`. A no-op if `slot_index` is beyond `head` (never marked) or has
+    /// already fallen out of the ring's retention window.
+    pub fn unmark(&mut self, slot_index: usize) {
+        if let Some(head) = self.head {
+            if slot_index <= head && head - slot_index < Self::MAX_ENTRIES {
+                self.set_bit(slot_index, false);
+            }
+        }
+    }
+
+    #[inline]
+    fn bit(&self, slot_index: usize) -> bool {
+        let ring_index = slot_index % Self::MAX_ENTRIES;
+        self.bits[ring_index / 64] & (1 << (ring_index % 64)) != 0
+    }
+
+    #[inline]
+    fn set_bit(&mut self, slot_index: usize, value: bool) {
+        let ring_index = slot_index % Self::MAX_ENTRIES;
+        if value {
+            self.bits[ring_index / 64] |= 1 << (ring_index % 64);
+        } else {
+            self.bits[ring_index / 64] &= !(1 << (ring_index % 64));
+        }
+    }
+}
+
+impl Default for SlotHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One slot holder, tracked alongside the bucket it's filed under so it can be given back
+/// (`release_slot`), shuffled earlier (`compress`), or reclaimed on expiry (`reap`).
+#[derive(Debug, Clone)]
+struct Reservation<T> {
+    item: T,
+    /// The earliest time this holder could actually use, independent of which slot it landed in.
+    /// `compress` never pulls a reservation earlier than this.
+    earliest: DateTime<Utc>,
+    assigned_time: DateTime<Utc>,
+    /// When set, `reap` reclaims this reservation if it's still unconfirmed once `now` passes
+    /// this deadline.
+    expires_at: Option<DateTime<Utc>>,
+    confirmed: bool,
+}
+
 #[derive(Debug)]
 pub struct CumulativeSmallSlotManager<T: PartialEq> {
     pub start: DateTime<Utc>,
-    pub hourly_accumulation_limit: Vec<u32>,
-    pub slots_assigned: RwLock<Vec<Vec<T>>>,
+    /// Cumulative capacity through the end of each segment (a running total of each segment's
+    /// own capacity), mirroring the old per-hour accumulation limit but over segments that no
+    /// longer need to be an hour wide.
+    pub accumulation_limit: Vec<u32>,
+    /// Each segment's start time, parallel to `accumulation_limit`/`window_duration`. Computed
+    /// once from the segment durations at construction, since neither changes afterward.
+    window_start: Vec<DateTime<Utc>>,
+    /// Each segment's length, parallel to `accumulation_limit`/`window_start`. Uniform (one hour)
+    /// for managers built via `new`, but `with_segments` allows arbitrary, non-uniform widths.
+    window_duration: Vec<TimeDelta>,
+    slots_assigned: RwLock<Vec<Vec<Reservation<T>>>>,
+    /// GCRA burst tolerance for `allocate_slot_gcra`: how far behind the theoretical arrival
+    /// time (`tat`) a request is still allowed to catch up rather than being rejected. Unused
+    /// by `allocate_slot`, which metes out by cumulative segment buckets instead.
+    gcra_tau: TimeDelta,
+    /// GCRA theoretical arrival time: the next instant at which admitting a request costs
+    /// nothing against the burst tolerance. Advances by `allocate_slot_gcra`'s emission interval
+    /// on every admitted request; starts at `start`, i.e. the program opens with a full `tau` of
+    /// slack already available.
+    gcra_tat: RwLock<DateTime<Utc>>,
+    /// Exact-time occupancy, at `fine_granularity` resolution, so `allocate_slot` can reject two
+    /// assignments that land on the identical instant instead of only tracking hourly counts.
+    fine_grained: RwLock<SlotHistory>,
+    fine_granularity: TimeDelta,
+    /// How far outside `[start, start + program length)` a request's timestamp can still fall
+    /// and be clamped into range rather than rejected. Zero (the default from `new`) reproduces
+    /// the original behavior of treating any out-of-range time as unschedulable.
+    time_tolerance: TimeDelta,
+    /// How long a fresh reservation holds its slot before `reap` can reclaim it for being
+    /// unconfirmed. `None` (the default from `new`) means reservations never expire.
+    reservation_ttl: Option<TimeDelta>,
 }
 
 macro_rules! prefix_sum {
@@ -362,22 +673,136 @@ impl<T: PartialEq> CumulativeSmallSlotManager<T> {
     const HOUR_SLACK: u32 = 3;
     const SLOT_DURATION: TimeDelta = TimeDelta::minutes(4);
 
+    /// Convenience constructor for the common case of one capacity integer per clock hour, e.g.
+    /// `vec![5, 5, 5, 1]`. Equivalent to `with_segments` with every segment an hour wide.
     pub fn new(start: DateTime<Utc>, throughput: Vec<u32>) -> Self {
+        Self::with_segments(
+            start,
+            throughput
+                .into_iter()
+                .map(|capacity| (TimeDelta::hours(1), capacity))
+                .collect(),
+        )
+    }
+
+    /// Build a program from an ordered list of `(window_duration, capacity)` segments, so
+    /// acceptance rates can change on sub-hourly or otherwise irregular boundaries instead of only
+    /// at clock-hour marks.
+    pub fn with_segments(start: DateTime<Utc>, segments: Vec<(TimeDelta, u32)>) -> Self {
+        let window_duration: Vec<TimeDelta> = segments.iter().map(|(duration, _)| *duration).collect();
+        let mut cursor = start;
+        let window_start: Vec<DateTime<Utc>> = window_duration
+            .iter()
+            .map(|duration| {
+                let this_start = cursor;
+                cursor += *duration;
+                this_start
+            })
+            .collect();
         Self {
             start,
             slots_assigned: RwLock::new(repeat_with(Vec::new)
-                .take(throughput.len())
+                .take(segments.len())
                 .collect()),
-            hourly_accumulation_limit: prefix_sum!(throughput.into_iter()).collect()
+            accumulation_limit: prefix_sum!(segments.iter().map(|(_, capacity)| *capacity)).collect(),
+            window_start,
+            window_duration,
+            gcra_tau: TimeDelta::zero(),
+            gcra_tat: RwLock::new(start),
+            fine_grained: RwLock::new(SlotHistory::new()),
+            fine_granularity: TimeDelta::minutes(1),
+            time_tolerance: TimeDelta::zero(),
+            reservation_ttl: None,
+        }
+    }
+
+    /// Set the GCRA burst tolerance used by `allocate_slot_gcra`. Defaults to zero (no burst
+    /// allowance) from `new`.
+    pub fn with_burst_tolerance(mut self, tau: TimeDelta) -> Self {
+        self.gcra_tau = tau;
+        self
+    }
+
+    /// Set the resolution at which `allocate_slot` tracks exact-time occupancy via `SlotHistory`.
+    /// Defaults to one minute from `new`.
+    pub fn with_fine_granularity(mut self, granularity: TimeDelta) -> Self {
+        self.fine_granularity = granularity;
+        self
+    }
+
+    /// Set how far outside the program's window (see `time_tolerance`) a request's timestamp can
+    /// still be clamped into range by `segment_index` rather than rejected. Defaults to zero.
+    pub fn with_time_tolerance(mut self, tolerance: TimeDelta) -> Self {
+        self.time_tolerance = tolerance;
+        self
+    }
+
+    /// Give every reservation allocated from now on a TTL: `reap` can reclaim it once it's this
+    /// far past the time it was requested for without having been `confirm_slot`-ed. Unset (the default from
+    /// `new`) means reservations never expire.
+    pub fn with_reservation_ttl(mut self, ttl: TimeDelta) -> Self {
+        self.reservation_ttl = Some(ttl);
+        self
+    }
+
+    /// Turn a query time into an index into `accumulation_limit`/`window_start`/`slots_assigned`.
+    /// Segments can be arbitrary widths (unlike `SlotClock`, which only models a single uniform
+    /// slot duration), so this walks `window_start` directly rather than reusing that type.
+    /// `time_tolerance` clamps a timestamp up to that far before `start`, or past the program's
+    /// end, into the first/last segment rather than rejecting it outright.
+    fn segment_index(&self, time: DateTime<Utc>) -> Option<usize> {
+        if self.window_start.is_empty() {
+            return None;
+        }
+        if time < self.start - self.time_tolerance {
+            return None;
+        }
+        let program_end = *self.window_start.last().unwrap() + *self.window_duration.last().unwrap();
+        if time >= program_end + self.time_tolerance {
+            return None;
         }
+        let clamped = std::cmp::min(std::cmp::max(time, self.start), program_end);
+        // `partition_point` finds the first segment starting after `clamped`; the containing
+        // segment is the one before it.
+        let index = self.window_start.partition_point(|&window_start| window_start <= clamped);
+        Some(index.saturating_sub(1))
+    }
+
+    /// The `fine_granularity`-resolution clock backing `fine_slot_index`'s exact-time occupancy
+    /// checks.
+    fn fine_clock(&self) -> SlotClock {
+        SlotClock::new(self.start, self.fine_granularity).with_tolerance(self.time_tolerance)
+    }
+
+    fn fine_slot_index(&self, time: &DateTime<Utc>) -> usize {
+        self.fine_clock().time_to_slot(*time).unwrap_or(0)
     }
 
     pub fn allocate_slot(&self, query_time: &DateTime<Utc>, item: T) -> Option<DateTime<Utc>> {
-        let query_index = (*query_time - self.start).num_hours() as u32;
         // Need to maintain exclusive write access to slots until after they are mutated
         let mut slots = self.slots_assigned.write().unwrap();
-        let accum = self.assigned_accumulation(&slots);
-        let first_with_capacity = std::iter::zip(accum.iter(), self.hourly_accumulation_limit.iter())
+        let expires_at = self.reservation_ttl.map(|ttl| *query_time + ttl);
+        self.place(&mut *slots, item, *query_time, expires_at, false)
+    }
+
+    /// Find the earliest open slot at or after `earliest`, honoring both the cumulative hourly
+    /// limits and the per-bucket/fine-grained checks `allocate_slot` has always used, and file
+    /// `item` there with the given TTL/confirmation state. Shared by `allocate_slot` and
+    /// `compress`, which both need to drop a holder into the earliest slot it's eligible for.
+    fn place(
+        &self,
+        slots: &mut [Vec<Reservation<T>>],
+        item: T,
+        earliest: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        confirmed: bool,
+    ) -> Option<DateTime<Utc>> {
+        // `usize::MAX` (rather than 0) on a time outside the program's tolerance-widened window
+        // so it skips every bucket below, i.e. is treated as unschedulable rather than as an
+        // early arrival.
+        let query_index = self.segment_index(earliest).unwrap_or(usize::MAX);
+        let accum = self.assigned_accumulation(slots);
+        let first_with_capacity = std::iter::zip(accum.iter(), self.accumulation_limit.iter())
             .enumerate()
             .rev()
             .skip_while(|&(_, (current, limit))| *current < *limit)
@@ -391,49 +816,182 @@ impl<T: PartialEq> CumulativeSmallSlotManager<T> {
 
         let first_ok_index = accum.into_iter()
             .enumerate()
-            .skip(std::cmp::max(query_index as usize, first_with_capacity.unwrap_or(0)))
+            .skip(std::cmp::max(query_index, first_with_capacity.unwrap_or(0)))
             .find(|&(i, assigned_accum)| {
-                let slot_limit = self.expected_throughput(i) + Self::HOUR_SLACK;
-                assigned_accum < self.hourly_accumulation_limit[i] && slots[i].len() < slot_limit as usize
+                let slot_limit = self.segment_capacity(i) + Self::HOUR_SLACK;
+                assigned_accum < self.accumulation_limit[i] && slots[i].len() < slot_limit as usize
             })
             .map(|i| i.0);
 
         if let Some(index) = first_ok_index {
             let slot_ordinal = slots[index].len();
-            slots[index].push(item);
             let time_estimate = self.slot_size(index) * slot_ordinal as i32;
-            Some(self.start + TimeDelta::hours(index as i64) + time_estimate)
+            let window_start = self.window_start[index];
+            let assigned_time = std::cmp::max(window_start + time_estimate, earliest);
+
+            let fine_index = self.fine_slot_index(&assigned_time);
+            let mut fine_grained = self.fine_grained.write().unwrap();
+            if fine_grained.is_taken(fine_index) {
+                // The hour-level bookkeeping above picked a time that's already spoken for at
+                // fine-grained resolution (e.g. a since-voided slot whose exact instant is still
+                // marked); don't double-book it.
+                return None;
+            }
+            fine_grained.mark(fine_index);
+
+            slots[index].push(Reservation {
+                item,
+                earliest,
+                assigned_time,
+                expires_at,
+                confirmed,
+            });
+            Some(assigned_time)
         } else { None }
     }
 
+    /// Give back a previously assigned slot, e.g. because its flight cancelled. Returns whether
+    /// `item` was found and released.
+    pub fn release_slot(&self, item: &T) -> bool {
+        let mut slots = self.slots_assigned.write().unwrap();
+        for bucket in slots.iter_mut() {
+            if let Some(pos) = bucket.iter().position(|r| r.item == *item) {
+                let reservation = bucket.remove(pos);
+                self.fine_grained.write().unwrap().unmark(self.fine_slot_index(&reservation.assigned_time));
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Mark `item`'s reservation as confirmed, so `reap` won't reclaim it once its TTL passes.
+    /// Returns whether `item` was found.
+    pub fn confirm_slot(&self, item: &T) -> bool {
+        let mut slots = self.slots_assigned.write().unwrap();
+        for bucket in slots.iter_mut() {
+            if let Some(reservation) = bucket.iter_mut().find(|r| r.item == *item) {
+                reservation.confirmed = true;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Reclaim every unconfirmed reservation whose TTL has passed `now`, freeing its capacity
+    /// back into the accumulation accounting. Reservations allocated without a TTL (see
+    /// `with_reservation_ttl`) are never reaped.
+    pub fn reap(&self, now: DateTime<Utc>) {
+        let mut slots = self.slots_assigned.write().unwrap();
+        let fine_grained = &self.fine_grained;
+        for bucket in slots.iter_mut() {
+            bucket.retain(|r| {
+                let expired = !r.confirmed && r.expires_at.is_some_and(|exp| exp <= now);
+                if expired {
+                    fine_grained.write().unwrap().unmark(self.fine_slot_index(&r.assigned_time));
+                }
+                !expired
+            });
+        }
+    }
+
+    /// Ground-Delay-Program-style slot compression: after cancellations free up early capacity,
+    /// pull every still-held reservation as far forward as its own `earliest` feasible time
+    /// allows. Reservations are re-placed in their current assigned-time order, so an earlier
+    /// holder gets first pick of the freed capacity; a later holder whose `earliest` is earlier
+    /// can still land ahead of one it used to follow.
+    pub fn compress(&self) {
+        let mut slots = self.slots_assigned.write().unwrap();
+        let mut held: Vec<Reservation<T>> = slots.iter_mut().flat_map(std::mem::take).collect();
+        held.sort_by_key(|r| r.assigned_time);
+
+        for reservation in held {
+            let fine_index = self.fine_slot_index(&reservation.assigned_time);
+            self.fine_grained.write().unwrap().unmark(fine_index);
+            let Reservation { item, earliest, expires_at, confirmed, .. } = reservation;
+            // Every reservation just vacated its own bucket, so there's always at least as much
+            // capacity available as before; in the pathological case `place` still can't find
+            // room (e.g. a fine-grained collision with another reservation's new instant), the
+            // holder is simply dropped rather than left double-booked.
+            self.place(&mut *slots, item, earliest, expires_at, confirmed);
+        }
+    }
+
+    /// Generic Cell Rate Algorithm metering: instead of admitting requests into per-segment
+    /// buckets until each fills up then rejecting the rest until the next segment
+    /// (`allocate_slot`'s behavior), this spreads admissions evenly at the target rate via a
+    /// single theoretical arrival time (`gcra_tat`). A request at `query_time`: if it's at or
+    /// after `gcra_tat`, it's accepted right at `query_time` and `gcra_tat` advances to
+    /// `query_time + T`; if it's late by no more than `gcra_tau`, it's still accepted but
+    /// scheduled at the (later) `gcra_tat`, which also advances by `T`; otherwise it's rejected.
+    /// `T`, the emission interval, is `segment width / rate` for whichever segment currently holds
+    /// `gcra_tat`, so the same segment programs driving `allocate_slot` work unchanged here too.
+    pub fn allocate_slot_gcra(&self, query_time: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut tat = self.gcra_tat.write().unwrap();
+        let index = self
+            .segment_index(*tat)
+            .unwrap_or(self.accumulation_limit.len() - 1);
+        let emission_interval = self.window_duration[index] / self.segment_capacity(index) as i32;
+
+        if *query_time >= *tat {
+            *tat = std::cmp::max(*tat, *query_time) + emission_interval;
+            Some(*query_time)
+        } else if *tat - *query_time <= self.gcra_tau {
+            let scheduled = *tat;
+            *tat += emission_interval;
+            Some(scheduled)
+        } else {
+            None
+        }
+    }
+
+    /// This segment's own (non-cumulative) capacity, recovered from the running totals in
+    /// `accumulation_limit`.
     #[inline]
-    fn expected_throughput(&self, i: usize) -> u32 {
-        self.hourly_accumulation_limit[i] -
+    fn segment_capacity(&self, i: usize) -> u32 {
+        self.accumulation_limit[i] -
             if i == 0 {
                 0
             } else {
-                self.hourly_accumulation_limit[i - 1]
+                self.accumulation_limit[i - 1]
             }
     }
 
     #[inline]
     fn slot_size(&self, i: usize) -> TimeDelta {
-        std::cmp::min(TimeDelta::hours(1) / self.expected_throughput(i) as i32, Self::SLOT_DURATION)
+        std::cmp::min(self.window_duration[i] / self.segment_capacity(i) as i32, Self::SLOT_DURATION)
     }
 
-    fn assigned_accumulation(&self, slots: &Vec<Vec<T>>) -> Vec<u32> {
+    fn assigned_accumulation(&self, slots: &[Vec<Reservation<T>>]) -> Vec<u32> {
         Box::new(prefix_sum!(Box::new(slots.iter().map(Vec::len))
             .map(|x| x as u32)))
             .collect()
     }
 }
 
+/// How `GroundDelayProgram::request_depart` orders competing flights for a scarce arrival slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GdpMode {
+    /// First come, first served: whichever flight asks for a slot first gets the earliest one.
+    #[default]
+    Greedy,
+    /// Ration by Schedule: slots are handed out in order of *originally scheduled* arrival time,
+    /// regardless of request order.
+    RationBySchedule,
+}
+
 #[derive(Debug)]
 pub struct GroundDelayProgram {
     pub site: AirportCode,
     // Room to add origin ARTCCs
     pub slots: SlotManager<FlightId>,
     pub reason: Option<String>,
+    pub mode: GdpMode,
+    /// RBS-mode-only priority queue: every eligible flight's id, ordered by scheduled arrival
+    /// time. A flight's slot is `self.site`-relative position `i` in this queue, mapped to hour
+    /// `i / max_slot_size` at offset `i % max_slot_size`. Lazily populated from `model.flights`
+    /// on first use; cancellations simply remove their id, which slides every later flight's
+    /// slot earlier for free.
+    pub rbs_queue: RwLock<Vec<FlightId>>,
 }
 
 impl GroundDelayProgram {
@@ -445,6 +1003,67 @@ impl GroundDelayProgram {
     pub fn end(&self) -> &DateTime<Utc> {
         &self.slots.end
     }
+
+    /// This flight's position in the RBS priority queue, populating the queue from every
+    /// eligible flight in `model` on first use, and appending any flight not already in it
+    /// (e.g. one whose estimated, but not scheduled, arrival falls in the window).
+    fn rbs_position(&self, flight_id: FlightId, model: &Model) -> usize {
+        let mut queue = self.rbs_queue.write().unwrap();
+        if queue.is_empty() {
+            let mut candidates: Vec<(DateTime<Utc>, FlightId)> = model
+                .flights
+                .values()
+                .filter_map(|flt| {
+                    let flt = flt.read().unwrap();
+                    if !flt.cancelled
+                        && flt.dest == self.site
+                        && flt.sched_arrive >= self.slots.start
+                        && flt.sched_arrive < self.slots.end
+                    {
+                        Some((flt.sched_arrive, flt.id))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            candidates.sort_by_key(|(sched_arrive, _)| *sched_arrive);
+            *queue = candidates.into_iter().map(|(_, id)| id).collect();
+        }
+        if let Some(pos) = queue.iter().position(|id| *id == flight_id) {
+            pos
+        } else {
+            queue.push(flight_id);
+            queue.len() - 1
+        }
+    }
+
+    fn request_depart_rbs(
+        &self,
+        flight: &Flight,
+        model: &Model,
+        time: &DateTime<Utc>,
+    ) -> Clearance {
+        if flight.dest != self.site {
+            return Clearance::Cleared;
+        }
+        let arrive = flight.est_arrive_time(time);
+        if !self.slots.contains(&arrive) {
+            return Clearance::Cleared;
+        }
+        let pos = self.rbs_position(flight.id, model);
+        let index = pos / self.slots.max_slot_size as usize;
+        if index >= self.slots.slots_assigned.len() {
+            // More eligible flights than the program has slots for; wait until it lifts.
+            return Clearance::Deferred(*self.end() - flight.est_duration());
+        }
+        let within = pos % self.slots.max_slot_size as usize;
+        let edct = self.slots.slot_time_estimate(index, within);
+        Clearance::EDCT(std::cmp::max(model.now(), edct) - flight.est_duration())
+    }
+
+    fn void_depart_clearance_rbs(&self, flight: &Flight) {
+        self.rbs_queue.write().unwrap().retain(|id| *id != flight.id);
+    }
 }
 
 impl Disruption for GroundDelayProgram {
@@ -454,6 +1073,9 @@ impl Disruption for GroundDelayProgram {
         model: &Model,
         time: &DateTime<Utc>,
     ) -> Clearance {
+        if self.mode == GdpMode::RationBySchedule {
+            return self.request_depart_rbs(flight, model, time);
+        }
         if flight.dest != self.site {
             return Clearance::Cleared;
         }
@@ -478,6 +1100,10 @@ impl Disruption for GroundDelayProgram {
     }
 
     fn void_depart_clearance(&mut self, flight: &Flight, time: &DateTime<Utc>, _model: &Model) {
+        if self.mode == GdpMode::RationBySchedule {
+            self.void_depart_clearance_rbs(flight);
+            return;
+        }
         let slot_time = flight.est_arrive_time(time);
         if self.slots.contains(&slot_time) {
             // println!("{} VOIDED departure clearance for flight {} at {:?} (slots used to be {:?})", self.describe(), flight.id, time, self.slots.slots_assigned);
@@ -514,6 +1140,17 @@ impl Disruption for GroundDelayProgram {
     fn departure_airports_affected(&self) -> Vec<AirportCode> {
         Vec::new()
     }
+
+    fn snapshot(&self) -> DisruptionRecord {
+        DisruptionRecord::GroundDelayProgram {
+            site: self.site,
+            start: *self.start(),
+            end: *self.end(),
+            hourly_rate: self.slots.max_slot_size,
+            reason: self.reason.clone(),
+            mode: self.mode,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -587,6 +1224,16 @@ impl Disruption for DepartureRateLimit {
     fn departure_airports_affected(&self) -> Vec<AirportCode> {
         vec![self.site]
     }
+
+    fn snapshot(&self) -> DisruptionRecord {
+        DisruptionRecord::DepartureRateLimit {
+            site: self.site,
+            start: self.slots.start,
+            end: self.slots.end,
+            hourly_rate: self.slots.max_slot_size,
+            reason: self.reason.clone(),
+        }
+    }
 }
 
 pub struct DisruptionIndex {
@@ -609,6 +1256,10 @@ impl DisruptionIndex {
         self.disruptions.len()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<RwLock<dyn Disruption>>> {
+        self.disruptions.iter()
+    }
+
     pub fn add_disruption(&mut self, disruption: Arc<RwLock<dyn Disruption>>) {
         let index = self.disruptions.len();
         self.disruptions.push(disruption);
@@ -681,6 +1332,7 @@ mod tests {
                 AirportCode::from(&"BWI".to_owned()),
             ],
             flights_taken: Vec::new(),
+            bag_weight: 0,
         };
         assert_eq!(psg.next_dest(psg.path[0]), Some(psg.path[1]));
         assert_eq!(psg.next_dest(psg.path[1]), Some(psg.path[2]));
@@ -696,6 +1348,120 @@ mod tests {
         assert_eq!(allocation, Some(now));
     }
 
+    fn rbs_test_flight(id: FlightId, dest: AirportCode, sched_arrive: DateTime<Utc>) -> Flight {
+        Flight {
+            id,
+            flight_number: format!("RBS{}", id),
+            aircraft_tail: None,
+            crew: vec![1],
+            passengers: Vec::new(),
+            origin: AirportCode::from(&"AAA".to_owned()),
+            dest,
+            cancelled: false,
+            depart_time: None,
+            arrive_time: None,
+            dep_delay: TimeDelta::zero(),
+            accum_delay: None,
+            sched_depart: sched_arrive - TimeDelta::hours(1),
+            sched_arrive,
+        }
+    }
+
+    fn rbs_test_model(now: DateTime<Utc>, flights: Vec<Flight>) -> Model {
+        let (publisher, _) = std::sync::mpsc::channel();
+        let (event_broadcast, _) = tokio::sync::broadcast::channel(1);
+        Model {
+            _now: Arc::new(RwLock::new(now)),
+            end: now + TimeDelta::hours(24),
+            fleet: HashMap::new(),
+            crew: HashMap::new(),
+            airports: HashMap::new(),
+            flights: flights
+                .into_iter()
+                .map(|f| (f.id, Arc::new(RwLock::new(f))))
+                .collect(),
+            disruptions: DisruptionIndex::new(),
+            publisher,
+            event_broadcast,
+            metrics: RwLock::new(None),
+            config: crate::model::ModelConfig {
+                crew_turnaround_time: TimeDelta::minutes(30),
+                aircraft_turnaround_time: TimeDelta::minutes(30),
+                max_delay: TimeDelta::hours(6),
+                aircraft_search_beam_width: u32::MAX,
+                aircraft_search_max_depth: 4,
+                crew_max_duty: TimeDelta::hours(10),
+                crew_min_rest: TimeDelta::hours(10),
+                aircraft_search_objective: "coverage".to_string(),
+                aircraft_max_ferry_legs: 2,
+                aircraft_max_ferry_duration: TimeDelta::hours(6),
+                assignment_window_violation_weight: 2.0,
+                assignment_deadhead_penalty: 30.0,
+                crew_duty_engine: crate::duty_rules::Far117LikeEngine {
+                    fdp_reduced_report_hours: TimeDelta::hours(8),
+                    fdp_base_report_hours: TimeDelta::hours(9),
+                    reduced_report_hour_start: 2,
+                    reduced_report_hour_end: 5,
+                    fdp_reduction_per_segment: TimeDelta::minutes(30),
+                    fdp_floor: TimeDelta::hours(8),
+                    min_rest_floor: TimeDelta::hours(10),
+                    min_rest_scale: 1.0,
+                    max_cumulative_flight_time: TimeDelta::hours(100),
+                    cumulative_window: TimeDelta::days(28),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn rbs_orders_by_scheduled_time_not_request_order() {
+        let now = Utc::now();
+        let site = AirportCode::from(&"ZZZ".to_owned());
+
+        // Scheduled to arrive late, but requests clearance first.
+        let late_scheduled = rbs_test_flight(1, site, now + TimeDelta::minutes(90));
+        // Scheduled to arrive early, but requests clearance second.
+        let early_scheduled = rbs_test_flight(2, site, now + TimeDelta::minutes(10));
+
+        let model = rbs_test_model(now, vec![late_scheduled.clone(), early_scheduled.clone()]);
+        let mut gdp = GroundDelayProgram {
+            site,
+            slots: SlotManager::new(now, now + TimeDelta::hours(2), 1),
+            reason: None,
+            mode: GdpMode::RationBySchedule,
+            rbs_queue: RwLock::new(Vec::new()),
+        };
+
+        let late_clearance = gdp.request_depart(&late_scheduled, &model, &now);
+        let early_clearance = gdp.request_depart(&early_scheduled, &model, &now);
+
+        assert!(early_clearance.time() < late_clearance.time());
+    }
+
+    #[test]
+    fn rbs_compresses_slots_after_cancellation() {
+        let now = Utc::now();
+        let site = AirportCode::from(&"ZZZ".to_owned());
+
+        let first = rbs_test_flight(1, site, now + TimeDelta::minutes(10));
+        let second = rbs_test_flight(2, site, now + TimeDelta::minutes(70));
+
+        let model = rbs_test_model(now, vec![first.clone(), second.clone()]);
+        let mut gdp = GroundDelayProgram {
+            site,
+            slots: SlotManager::new(now, now + TimeDelta::hours(2), 1),
+            reason: None,
+            mode: GdpMode::RationBySchedule,
+            rbs_queue: RwLock::new(Vec::new()),
+        };
+
+        let before = gdp.request_depart(&second, &model, &now);
+        gdp.void_depart_clearance(&first, &now, &model);
+        let after = gdp.request_depart(&second, &model, &now);
+
+        assert!(after.time() < before.time());
+    }
+
     #[test]
     fn cssm_constant_rate_assign() {
         let now = Utc::now();
@@ -757,6 +1523,212 @@ mod tests {
 
     #[test]
     fn cssm_slot_exists_already() {
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::new(now, vec![5, 5, 5, 1]);
+        // Force the exact instant `allocate_slot` would otherwise compute for the next
+        // assignment to already be marked taken at fine-grained resolution.
+        man.fine_grained.write().unwrap().mark(man.fine_slot_index(&now));
+        assert_eq!(man.allocate_slot(&now, 1), None);
+        // A request landing on a different instant is unaffected.
+        assert!(man.allocate_slot(&(now + TimeDelta::minutes(5)), 2).is_some());
+    }
+
+    #[test]
+    fn cssm_time_tolerance_clamps_pre_program_request() {
+        let now = Utc::now();
+        // Without tolerance, a request a few seconds before `start` is outside the program
+        // window and gets nothing.
+        let strict = CumulativeSmallSlotManager::<FlightId>::new(now, vec![5, 5, 5, 1]);
+        assert_eq!(strict.allocate_slot(&(now - TimeDelta::seconds(5)), 1), None);
+
+        // With tolerance, the same early request clamps into the opening slot instead.
+        let tolerant = CumulativeSmallSlotManager::<FlightId>::new(now, vec![5, 5, 5, 1])
+            .with_time_tolerance(TimeDelta::minutes(1));
+        assert!(tolerant.allocate_slot(&(now - TimeDelta::seconds(5)), 1).is_some());
+    }
+
+    #[test]
+    fn cssm_with_segments_honors_non_uniform_window_capacity() {
+        // A 15-minute segment capped at 1, followed by a 45-minute segment capped at 3 (4
+        // cumulative), mimicking a capacity ramp shortly after a weather recovery begins.
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::with_segments(
+            now,
+            vec![(TimeDelta::minutes(15), 1), (TimeDelta::minutes(45), 3)],
+        );
+
+        assert_eq!(man.allocate_slot(&now, 1), Some(now));
+        // The first segment is full; the second request spills into the second segment even
+        // though it's requested within the first segment's (15-minute) window.
+        assert_eq!(man.allocate_slot(&now, 2), Some(now + TimeDelta::minutes(15)));
+        assert!(man.allocate_slot(&(now + TimeDelta::minutes(20)), 3).is_some());
+        assert!(man.allocate_slot(&(now + TimeDelta::minutes(25)), 4).is_some());
+        // Both segments are now at their cumulative limit (1 and 4).
+        assert_eq!(man.allocate_slot(&now, 5), None);
+    }
+
+    #[test]
+    fn slot_history_future_found_not_found_too_old() {
+        let mut history = SlotHistory::new();
+        assert_eq!(history.check(0), Check::Future);
+
+        history.mark(5);
+        assert_eq!(history.check(5), Check::Found);
+        assert_eq!(history.check(3), Check::NotFound);
+        assert_eq!(history.check(6), Check::Future);
+
+        history.mark(5 + SlotHistory::MAX_ENTRIES + 1);
+        assert_eq!(history.check(5), Check::TooOld);
+    }
+
+    #[test]
+    fn slot_history_wrap_reuses_ring_slot_for_new_head() {
+        let mut history = SlotHistory::new();
+        history.mark(2);
+        history.mark(2 + SlotHistory::MAX_ENTRIES);
+        // Index 2's ring slot was reused by the wrapped mark above, and is the freshly marked
+        // head itself, so it's still found...
+        assert_eq!(history.check(2 + SlotHistory::MAX_ENTRIES), Check::Found);
+        // ...but the original index 2 is now too old to have a decided status.
+        assert_eq!(history.check(2), Check::TooOld);
+    }
+
+    #[test]
+    fn cssm_gcra_meters_at_fixed_interval() {
+        // rate 4/hour -> emission interval 15 minutes, no burst tolerance.
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::new(now, vec![4, 4, 4, 4]);
+
+        assert_eq!(man.allocate_slot_gcra(&now), Some(now));
+        // A second request hot on the heels of the first has nothing to catch up with
+        // (tau defaults to zero), so it's rejected rather than queued.
+        assert_eq!(man.allocate_slot_gcra(&now), None);
+        // One emission interval later, the program has caught up and admits it right on time.
+        assert_eq!(
+            man.allocate_slot_gcra(&(now + TimeDelta::minutes(15))),
+            Some(now + TimeDelta::minutes(15))
+        );
+    }
+
+    #[test]
+    fn cssm_gcra_burst_tolerance_admits_late_arrivals_at_the_tat() {
+        // Same rate as above, but with enough burst tolerance to absorb one extra request
+        // arriving at the same instant as the first.
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::new(now, vec![4, 4, 4, 4])
+            .with_burst_tolerance(TimeDelta::minutes(20));
+
+        assert_eq!(man.allocate_slot_gcra(&now), Some(now));
+        // tat is now now+15m; this request is 15m behind, within the 20m tolerance, so it's
+        // admitted but scheduled at the tat rather than its requested time.
+        assert_eq!(man.allocate_slot_gcra(&now), Some(now + TimeDelta::minutes(15)));
+        // A third request this far behind exceeds the tolerance and is rejected.
+        assert_eq!(man.allocate_slot_gcra(&now), None);
+    }
+
+    #[test]
+    fn cssm_release_slot_frees_capacity_for_a_later_request() {
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::new(now, vec![1, 1, 1, 1]);
+        assert_eq!(man.allocate_slot(&now, 1), Some(now));
+        assert_eq!(man.allocate_slot(&now, 2), Some(now + TimeDelta::hours(1)));
+
+        assert!(man.release_slot(&1));
+        // The first hour is open again now that its sole holder gave it back.
+        assert_eq!(man.allocate_slot(&now, 3), Some(now));
+        // Releasing an item that was never assigned (or already released) is a no-op.
+        assert!(!man.release_slot(&1));
+    }
+
+    #[test]
+    fn cssm_compress_pulls_later_flight_into_vacated_earlier_slot() {
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::new(now, vec![1, 1, 1, 1]);
+        assert_eq!(man.allocate_slot(&now, 1), Some(now));
+        assert_eq!(man.allocate_slot(&now, 2), Some(now + TimeDelta::hours(1)));
+
+        man.release_slot(&1);
+        man.compress();
+
+        // Flight 2 never asked for anything before `now`, so compression pulls it all the way
+        // forward into the slot flight 1 vacated.
+        assert_eq!(man.allocate_slot(&now, 3), Some(now + TimeDelta::hours(1)));
+    }
+
+    #[test]
+    fn cssm_compress_respects_earliest_feasible_time() {
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::new(now, vec![1, 1, 1, 1]);
+        assert_eq!(man.allocate_slot(&now, 1), Some(now));
+        // Flight 2 couldn't have used anything before an hour in, even though a slot's free.
+        assert_eq!(man.allocate_slot(&(now + TimeDelta::hours(1)), 2), Some(now + TimeDelta::hours(1)));
+
+        man.release_slot(&1);
+        man.compress();
+
+        // Flight 2 stays put: its `earliest` rules out the now-vacant first hour.
+        assert_eq!(man.allocate_slot(&now, 3), Some(now));
+    }
+
+    #[test]
+    fn cssm_reap_reclaims_unconfirmed_expired_reservation() {
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::new(now, vec![1, 1, 1, 1])
+            .with_reservation_ttl(TimeDelta::minutes(10));
+        assert_eq!(man.allocate_slot(&now, 1), Some(now));
+
+        // Still within the TTL: nothing to reap yet.
+        man.reap(now + TimeDelta::minutes(5));
+        assert_eq!(man.allocate_slot(&now, 2), Some(now + TimeDelta::hours(1)));
+
+        man.reap(now + TimeDelta::minutes(11));
+        // The first hour's reservation expired unconfirmed and was reclaimed.
+        assert_eq!(man.allocate_slot(&now, 3), Some(now));
+    }
+
+    #[test]
+    fn cssm_reap_spares_confirmed_reservation() {
+        let now = Utc::now();
+        let man = CumulativeSmallSlotManager::<FlightId>::new(now, vec![1, 1, 1, 1])
+            .with_reservation_ttl(TimeDelta::minutes(10));
+        assert_eq!(man.allocate_slot(&now, 1), Some(now));
+        assert!(man.confirm_slot(&1));
+
+        man.reap(now + TimeDelta::minutes(11));
+        // Confirmed, so it survives past its TTL; the first hour is still taken.
+        assert_eq!(man.allocate_slot(&now, 2), Some(now + TimeDelta::hours(1)));
+    }
+
+    #[test]
+    fn deduct_passengers_is_gated_by_cargo_weight_not_just_seats() {
+        let now = Utc::now();
+        let origin = AirportCode::from(&"AAA".to_owned());
+        let dest = AirportCode::from(&"BBB".to_owned());
+
+        let mut airport = Airport {
+            code: origin,
+            fleet: HashSet::new(),
+            crew: HashSet::new(),
+            passengers: vec![PassengerDemand {
+                path: vec![origin, dest],
+                count: 10,
+                flights_taken: Vec::new(),
+                bag_weight: 1,
+            }],
+            max_dep_per_hour: 10,
+            max_arr_per_hour: 10,
+            departure_bucket: TokenBucket::new(10, now),
+            arrival_bucket: TokenBucket::new(10, now),
+        };
+        let model = rbs_test_model(now, Vec::new());
+        let mut onboard = Vec::new();
+
+        // 10 seats of room, but only 5 units of belly capacity at 1 unit/passenger: the cargo
+        // dimension should cap boarding well short of the seat count.
+        airport.deduct_passengers(1, dest, [10, 5], &mut onboard, now, &model);
 
+        let boarded: u32 = onboard.iter().map(|demand| demand.count).sum();
+        assert_eq!(boarded, 5);
+        assert_eq!(airport.passengers[0].count, 5);
     }
 }
@@ -0,0 +1,115 @@
+//! Insertion-cost scoring for the dispatcher's fallback aircraft/crew selectors.
+//!
+//! Without this, a fallback selector picks whichever feasible resource happens to be available
+//! earliest (`Aircraft::available_time`/`Crew::time_until_available_for` only report feasibility
+//! plus an instant). That's fine until two candidates are close in time but one would also have
+//! to connect in from elsewhere while the other is already sitting at the origin, or one candidate
+//! only barely beats the model's acceptable delay window while another badly blows through it, or
+//! one candidate is already booked on a tight turn to another flight while the other is free for
+//! the rest of the day. `insertion_cost` turns "earliest" into "cheapest": the schedule delay
+//! `flight` itself picks up, plus an extra per-minute penalty for the part of that delay beyond
+//! `max_delay`, plus a flat penalty if the candidate isn't already on the ground at the flight's
+//! origin, plus — the VRP-style part — whatever that delay cascades into on every later flight
+//! already on the candidate's route, computed the same way, chained until a turnaround absorbs it.
+
+use std::cmp::max;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::aircraft::{Flight, FlightId, Location};
+use crate::crew::CrewId;
+use crate::model::Model;
+
+/// Identifies which resource `insertion_cost` is scoring, so it knows both the turnaround time to
+/// cascade with and which flights in `model` make up the rest of its route.
+pub enum Resource<'a> {
+    Aircraft(&'a str),
+    Crew(CrewId),
+}
+
+impl Resource<'_> {
+    fn assigned_to(&self, flight: &Flight) -> bool {
+        match self {
+            Resource::Aircraft(tail) => flight.aircraft_tail.as_deref() == Some(*tail),
+            Resource::Crew(id) => flight.crew.contains(id),
+        }
+    }
+
+    fn turnaround(&self, model: &Model) -> TimeDelta {
+        match self {
+            Resource::Aircraft(_) => model.config.aircraft_turnaround_time,
+            Resource::Crew(_) => model.config.crew_turnaround_time,
+        }
+    }
+}
+
+/// The rest of `resource`'s route after `flight`: every other non-cancelled flight already
+/// assigned to it, scheduled to depart after `flight` does, in departure order.
+fn route_after(model: &Model, flight: &Flight, resource: &Resource) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    // Compare keys before locking, not `other.id` after: `flight` may already be held under a
+    // write lock by the caller (e.g. the crew fallback selector), and its `Arc<RwLock<_>>` would
+    // deadlock on a second `.read()` from the same thread.
+    let mut route: Vec<(FlightId, DateTime<Utc>, DateTime<Utc>)> = model
+        .flights
+        .iter()
+        .filter(|&(&id, _)| id != flight.id)
+        .filter_map(|(_, other)| {
+            let other = other.read().unwrap();
+            if other.cancelled || other.sched_depart <= flight.sched_depart {
+                return None;
+            }
+            resource
+                .assigned_to(&other)
+                .then_some((other.id, other.sched_depart, other.sched_arrive))
+        })
+        .collect();
+    route.sort_by_key(|&(id, sched_depart, _)| (sched_depart, id));
+    route
+        .into_iter()
+        .map(|(_, sched_depart, sched_arrive)| (sched_depart, sched_arrive))
+        .collect()
+}
+
+/// Minutes a delayed-to-`pushed_depart` flight costs relative to its own `sched_depart`/
+/// `max_delay` window — the same scoring `insertion_cost` applies to `flight` itself, reused for
+/// every flight the assignment cascades into.
+fn leg_cost(model: &Model, sched_depart: DateTime<Utc>, pushed_depart: DateTime<Utc>) -> f64 {
+    let duration_added = max(TimeDelta::zero(), pushed_depart - sched_depart).num_minutes() as f64;
+    let window_end = sched_depart + model.config.max_delay;
+    let violation_minutes = max(TimeDelta::zero(), pushed_depart - window_end).num_minutes() as f64;
+    duration_added + violation_minutes * model.config.assignment_window_violation_weight
+}
+
+/// Marginal cost (in delay-equivalent minutes) of assigning a resource that becomes available at
+/// `available_at`, currently at `location`, to `flight`. Lower is better; callers should pick the
+/// feasible candidate with the smallest cost rather than the smallest `available_at`. Models the
+/// candidate as a route: besides the delay `flight` itself would absorb, every later flight
+/// already assigned to the same resource (see `Resource`) is pushed back in turn by whatever the
+/// turnaround time doesn't absorb, and scored the same way, until one turnaround absorbs the rest.
+pub fn insertion_cost(
+    model: &Model,
+    flight: &Flight,
+    available_at: DateTime<Utc>,
+    location: Location,
+    resource: Resource,
+) -> f64 {
+    let deadhead_penalty = match location {
+        Location::Ground(airport, _) if airport == flight.origin => 0.0,
+        _ => model.config.assignment_deadhead_penalty,
+    };
+    let mut total = leg_cost(model, flight.sched_depart, available_at) + deadhead_penalty;
+
+    let turnaround = resource.turnaround(model);
+    let mut free_at = max(available_at, flight.sched_depart) + (flight.sched_arrive - flight.sched_depart)
+        + turnaround;
+    for (sched_depart, sched_arrive) in route_after(model, flight, &resource) {
+        let pushed_depart = max(sched_depart, free_at);
+        if pushed_depart <= sched_depart {
+            // This turnaround absorbed the whole cascade; nothing further down the route moves.
+            break;
+        }
+        total += leg_cost(model, sched_depart, pushed_depart);
+        free_at = pushed_depart + (sched_arrive - sched_depart) + turnaround;
+    }
+    total
+}
@@ -0,0 +1,205 @@
+//! Conflict-aware flight scheduling, in the spirit of Solana's `PrioGraphScheduler`.
+//!
+//! `Dispatcher::update_queue` orders purely by time, so two flights that depend on the same
+//! aircraft tail (an inbound leg feeding its outbound turn) or the same crew duty chain can be
+//! processed in whichever order the heap happens to pop them, letting a later-but-more-critical
+//! flight's resource get wrongly committed to an earlier one. `PrioGraph` builds a DAG over
+//! flights sharing a resource, directed by their scheduled order on it, so `Dispatcher` can hold a
+//! flight back while the flight scheduled just before it on a shared resource has higher priority
+//! and hasn't resolved yet. Only that immediate predecessor on each resource is checked.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::aircraft::FlightId;
+use crate::model::Model;
+
+/// A DAG over flights that share an aircraft tail or a crew member, built once from the
+/// scenario's initial schedule. An edge `blocker -> blocked` means `blocked` is scheduled after
+/// `blocker` on some resource they both use.
+#[derive(Debug)]
+pub struct PrioGraph {
+    /// A flight's priority: higher goes first when contending for a shared resource. Uses the
+    /// flight's own passenger count as a simple stand-in for "how much is riding on this leg",
+    /// matching how `objective.rs` already treats passenger count as the thing worth protecting.
+    priority: HashMap<FlightId, u64>,
+    /// `blocked -> [blocker, ...]`, in scheduled order on the shared resource.
+    blockers: HashMap<FlightId, Vec<FlightId>>,
+    /// Flights that haven't yet departed or been cancelled; a flight only blocks successors
+    /// while its own resource commitment is still outstanding.
+    pending: HashSet<FlightId>,
+}
+
+impl PrioGraph {
+    pub fn build(model: &Model) -> Self {
+        let mut priority = HashMap::new();
+        let mut pending = HashSet::new();
+        let mut by_tail: HashMap<String, Vec<FlightId>> = HashMap::new();
+        let mut by_crew: HashMap<crate::crew::CrewId, Vec<FlightId>> = HashMap::new();
+
+        for flight in model.flights.values() {
+            let flt = flight.read().unwrap();
+            priority.insert(flt.id, flt.passengers.iter().map(|d| d.count as u64).sum());
+            if !flt.cancelled {
+                pending.insert(flt.id);
+            }
+            if let Some(tail) = &flt.aircraft_tail {
+                by_tail.entry(tail.clone()).or_default().push(flt.id);
+            }
+            for crew in &flt.crew {
+                by_crew.entry(*crew).or_default().push(flt.id);
+            }
+        }
+
+        let mut blockers: HashMap<FlightId, Vec<FlightId>> = HashMap::new();
+        for mut group in by_tail.into_values().chain(by_crew.into_values()) {
+            group.sort_by_key(|id| model.flight_read(*id).sched_depart);
+            for pair in group.windows(2) {
+                blockers.entry(pair[1]).or_default().push(pair[0]);
+            }
+        }
+
+        Self { priority, blockers, pending }
+    }
+
+    /// The still-pending predecessor with strictly higher priority than `flight` that `flight`
+    /// must wait on, if any. `None` means `flight` is clear to claim its resources and depart as
+    /// far as the conflict graph is concerned.
+    pub fn blocking_predecessor(&self, flight: FlightId) -> Option<FlightId> {
+        let own_priority = self.priority.get(&flight).copied().unwrap_or(0);
+        self.blockers
+            .get(&flight)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|blocker| self.pending.contains(blocker))
+            .filter(|blocker| self.priority.get(blocker).copied().unwrap_or(0) > own_priority)
+            .max_by_key(|blocker| self.priority.get(blocker).copied().unwrap_or(0))
+    }
+
+    /// Mark `flight` as resolved (departed or cancelled), so it no longer blocks successors.
+    pub fn resolve(&mut self, flight: FlightId) {
+        self.pending.remove(&flight);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aircraft::Flight;
+    use crate::airport::{AirportCode, PassengerDemand};
+    use crate::model::{Model, ModelConfig};
+    use chrono::{DateTime, TimeDelta, Utc};
+    use std::collections::HashMap;
+
+    fn test_model(now: DateTime<Utc>, flights: Vec<Flight>) -> Model {
+        let (publisher, _) = std::sync::mpsc::channel();
+        let (event_broadcast, _) = tokio::sync::broadcast::channel(1);
+        Model {
+            _now: std::sync::Arc::new(std::sync::RwLock::new(now)),
+            end: now + TimeDelta::hours(24),
+            fleet: HashMap::new(),
+            crew: HashMap::new(),
+            airports: HashMap::new(),
+            flights: flights
+                .into_iter()
+                .map(|f| (f.id, std::sync::Arc::new(std::sync::RwLock::new(f))))
+                .collect(),
+            disruptions: crate::airport::DisruptionIndex::new(),
+            publisher,
+            event_broadcast,
+            metrics: std::sync::RwLock::new(None),
+            config: ModelConfig {
+                crew_turnaround_time: TimeDelta::minutes(30),
+                aircraft_turnaround_time: TimeDelta::minutes(30),
+                max_delay: TimeDelta::hours(6),
+                aircraft_search_beam_width: u32::MAX,
+                aircraft_search_max_depth: 4,
+                crew_max_duty: TimeDelta::hours(10),
+                crew_min_rest: TimeDelta::hours(10),
+                aircraft_search_objective: "coverage".to_string(),
+                aircraft_max_ferry_legs: 2,
+                aircraft_max_ferry_duration: TimeDelta::hours(6),
+                assignment_window_violation_weight: 2.0,
+                assignment_deadhead_penalty: 30.0,
+                crew_duty_engine: crate::duty_rules::Far117LikeEngine {
+                    fdp_reduced_report_hours: TimeDelta::hours(8),
+                    fdp_base_report_hours: TimeDelta::hours(9),
+                    reduced_report_hour_start: 2,
+                    reduced_report_hour_end: 5,
+                    fdp_reduction_per_segment: TimeDelta::minutes(30),
+                    fdp_floor: TimeDelta::hours(8),
+                    min_rest_floor: TimeDelta::hours(10),
+                    min_rest_scale: 1.0,
+                    max_cumulative_flight_time: TimeDelta::hours(100),
+                    cumulative_window: TimeDelta::days(28),
+                },
+            },
+        }
+    }
+
+    fn test_flight(id: FlightId, tail: &str, sched_depart: DateTime<Utc>, passengers: u32) -> Flight {
+        Flight {
+            id,
+            flight_number: format!("F{}", id),
+            aircraft_tail: Some(tail.to_string()),
+            crew: vec![1],
+            passengers: if passengers > 0 {
+                vec![PassengerDemand {
+                    path: vec![
+                        AirportCode::from(&"AAA".to_owned()),
+                        AirportCode::from(&"BBB".to_owned()),
+                    ],
+                    count: passengers,
+                    flights_taken: Vec::new(),
+                    bag_weight: 0,
+                }]
+            } else {
+                Vec::new()
+            },
+            origin: AirportCode::from(&"AAA".to_owned()),
+            dest: AirportCode::from(&"BBB".to_owned()),
+            cancelled: false,
+            depart_time: None,
+            arrive_time: None,
+            dep_delay: TimeDelta::zero(),
+            accum_delay: None,
+            sched_depart,
+            sched_arrive: sched_depart + TimeDelta::hours(1),
+        }
+    }
+
+    #[test]
+    fn later_leg_blocks_on_pending_higher_priority_predecessor_sharing_a_tail() {
+        let now = Utc::now();
+        let model = test_model(now, vec![
+            test_flight(1, "N1", now, 200),
+            test_flight(2, "N1", now + TimeDelta::hours(1), 10),
+        ]);
+        let graph = PrioGraph::build(&model);
+        assert_eq!(graph.blocking_predecessor(2), Some(1));
+        assert_eq!(graph.blocking_predecessor(1), None);
+    }
+
+    #[test]
+    fn resolving_the_predecessor_unblocks_the_successor() {
+        let now = Utc::now();
+        let model = test_model(now, vec![
+            test_flight(1, "N1", now, 200),
+            test_flight(2, "N1", now + TimeDelta::hours(1), 10),
+        ]);
+        let mut graph = PrioGraph::build(&model);
+        graph.resolve(1);
+        assert_eq!(graph.blocking_predecessor(2), None);
+    }
+
+    #[test]
+    fn lower_priority_predecessor_does_not_block() {
+        let now = Utc::now();
+        let model = test_model(now, vec![
+            test_flight(1, "N1", now, 5),
+            test_flight(2, "N1", now + TimeDelta::hours(1), 200),
+        ]);
+        let graph = PrioGraph::build(&model);
+        assert_eq!(graph.blocking_predecessor(2), None);
+    }
+}
@@ -2,12 +2,12 @@ use crate::aircraft::{Flight, FlightId, Location};
 use crate::airport::AirportCode;
 use crate::model::Model;
 use chrono::{DateTime, Duration, TimeDelta, Utc};
-use std::cmp::{max, min};
+use serde::{Deserialize, Serialize};
+use std::cmp::max;
 
 pub type CrewId = u32;
-pub const DUTY_HOURS: i64 = 10;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Crew {
     pub id: CrewId,
     pub location: Location,
@@ -26,28 +26,6 @@ impl Crew {
         }
     }
 
-    pub fn remaining_after(&self, flight: &Flight, model: &Model) -> Duration {
-        self.remaining_after_time(flight, model.now(), model)
-    }
-
-    pub fn remaining_after_time(
-        &self,
-        flight: &Flight,
-        now: DateTime<Utc>,
-        model: &Model,
-    ) -> Duration {
-        // formula: did we exceed 10-x hours of flight time
-        // in the past 24-x hours, where x is the next flight's duration?
-        let flight_duration = flight
-            .sched_arrive
-            .signed_duration_since(flight.sched_depart);
-        let interval_start = &(now - Duration::hours(24) + flight_duration);
-        let interval_end = &now;
-        let duty_after = self.duty_during(interval_start, interval_end, model) + flight_duration;
-
-        Duration::hours(DUTY_HOURS) - duty_after
-    }
-
     pub fn takeoff(&mut self, flight: &Flight) {
         self.location = Location::InFlight(flight.id);
         if flight.crew[0] == self.id {
@@ -67,17 +45,12 @@ impl Crew {
         self.location = Location::Ground(fl.dest, now);
     }
 
-    fn duty_during(&self, start: &DateTime<Utc>, end: &DateTime<Utc>, model: &Model) -> Duration {
-        self.duty
-            .iter()
-            .rev()
-            .skip_while(|flt| model.flight_read(**flt).depart_time.unwrap() >= *end)
-            .take_while(|flt| {
-                let flt = model.flight_read(**flt);
-                flt.arrive_time.unwrap_or(flt.act_arrive_time()) >= *start
-            })
-            .map(|flt| duration_in_range(&model.flight_read(*flt), start, end))
-            .sum()
+    /// Whether this crew member could legally operate `flight` next, per
+    /// `model.config.crew_duty_engine`'s flight-duty-period, rest, and cumulative-flight-time
+    /// limits. Exposed separately from `time_until_available_for` so a caller can tell a crew
+    /// member who is merely tied up right now apart from one who can never legally take the leg.
+    pub fn legal_for(&self, flight: &Flight, model: &Model) -> bool {
+        model.config.crew_duty_engine.legal_to_add(self, flight, model)
     }
 
     pub fn time_until_available_for(
@@ -91,23 +64,21 @@ impl Crew {
                 return None;
             }
         }
+        if !self.legal_for(flight, model) {
+            return None;
+        }
         let turnaround_time = model.config.crew_turnaround_time;
         match self.location {
             Location::Ground(location, since) => {
                 if location != flight.origin {
                     return None;
                 }
-                if self.remaining_after(flight, model) < Duration::zero() {
-                    return None;
-                }
                 let available_time = since + turnaround_time;
                 Some(max(Duration::zero(), available_time - now))
             }
             Location::InFlight(ongoing) => {
                 let ongoing_flt = model.flight_read(ongoing);
-                if self.remaining_after_time(flight, ongoing_flt.act_arrive_time(), model)
-                    < Duration::zero() || ongoing_flt.dest != flight.origin
-                {
+                if ongoing_flt.dest != flight.origin {
                     return None;
                 }
                 Some(ongoing_flt.act_arrive_time() + turnaround_time - now)
@@ -126,8 +97,3 @@ impl Crew {
         }
     }
 }
-
-fn duration_in_range(flight: &Flight, start: &DateTime<Utc>, end: &DateTime<Utc>) -> Duration {
-    min(&flight.arrive_time.unwrap_or(flight.act_arrive_time()), end)
-        .signed_duration_since(max(&flight.depart_time.unwrap(), start))
-}
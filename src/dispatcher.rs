@@ -15,8 +15,11 @@ use std::{
 
 use crate::{
     aircraft::{Flight, FlightId},
+    airport::AirportCode,
+    assignment_cost,
+    conflict_graph::PrioGraph,
     crew::CrewId,
-    metrics::{CancelReason, DelayReason, ModelEvent, ModelEventType},
+    metrics::{CancelReason, DelayReason, DispatcherStats, ModelEvent, ModelEventType},
     model::Model,
 };
 use chrono::{DateTime, TimeDelta, Utc};
@@ -52,6 +55,19 @@ impl Ord for DispatcherUpdate {
 
 static RESOURCE_WAIT: TimeDelta = TimeDelta::minutes(10);
 
+/// A synthetic empty repositioning leg a search backend wants flown so a surplus aircraft can
+/// reach an airport with unfulfilled flights it otherwise couldn't cover. Not itself a `Flight`
+/// in `Model::flights` (nothing boards it); it's the search's proposal for the dispatcher to turn
+/// into one.
+#[derive(Clone, Debug)]
+pub struct FerryLeg {
+    pub aircraft: String,
+    pub origin: AirportCode,
+    pub dest: AirportCode,
+    pub depart: DateTime<Utc>,
+    pub duration: TimeDelta,
+}
+
 pub trait AircraftSelectionStrategy {
     /// Reassign the given flight to any aircraft. The aircraft should be at the flight's origin
     /// or arriving at the flight's origin.
@@ -62,6 +78,12 @@ pub trait AircraftSelectionStrategy {
         HashMap::new()
     }
 
+    /// Ferry legs the last `select`/`reassign_suggestions` run wants flown to reposition surplus
+    /// aircraft. Most strategies never propose any.
+    fn ferry_legs(&self, _model: &Model) -> Vec<FerryLeg> {
+        Vec::new()
+    }
+
     fn on_flight_cancel(&mut self, _flight: FlightId, _model: &Model) {}
     fn on_flight_depart(&mut self, _flight: FlightId, _model: &Model) {}
 }
@@ -95,6 +117,49 @@ pub struct Dispatcher {
 
     pub update_queue: BinaryHeap<DispatcherUpdate>,
     pub aircraft_reassigned: HashSet<FlightId>,
+
+    /// When true, `CheckDepart` updates due at the same instant are grouped and assigned
+    /// aircraft together (`batch_assign_aircraft`) rather than each independently claiming the
+    /// earliest reachable tail via the fallback selector in processing order — the order flights
+    /// happen to be popped from `update_queue` shouldn't decide which one wins a scarce tail.
+    /// Only affects the fallback-selector path (`aircraft_selector` being `None`); a flight with
+    /// a full `AircraftSelectionStrategy` configured is left to `update_flight` as before.
+    pub batch_assign: bool,
+    /// Sort key `batch_assign` uses to decide which of several due, aircraft-less flights gets
+    /// first pick of the earliest-available compatible aircraft; lower sorts first. Swappable for
+    /// a proper Hungarian/auction solver later without touching the rest of the assignment pass.
+    pub batch_assign_cost: Box<dyn Fn(&Model, FlightId) -> f64>,
+
+    /// When set, `run_model` builds this once up front and consults it before letting a
+    /// `CheckDepart` flight claim resources and depart: a flight with a still-pending,
+    /// higher-priority predecessor sharing its tail or crew is requeued behind that predecessor
+    /// instead, preventing a reassigner from handing a tail to the wrong leg of a chain.
+    pub conflict_graph: Option<PrioGraph>,
+
+    /// Starting re-check delay for a flight that has no aircraft/crew candidate at all (as
+    /// opposed to one that's merely running late), following the `kube_runtime` backoff-and-
+    /// requeue model: each consecutive empty attempt doubles the wait, up to `resource_wait_cap`.
+    pub resource_wait_base: TimeDelta,
+    /// Ceiling on the backoff computed from `resource_wait_base`.
+    pub resource_wait_cap: TimeDelta,
+    /// Once a flight has been backing off for longer than this (measured from its first empty
+    /// attempt), `next_resource_wait` gives up and the caller cancels it with
+    /// `CancelReason::DelayTimedOut` instead of requeuing it again.
+    pub max_resource_wait: TimeDelta,
+    /// Per-flight `(attempts so far, time of the first empty attempt)`, used to compute the next
+    /// backoff delay and to detect `max_resource_wait` being exceeded. Reset once a flight departs
+    /// or is cancelled.
+    pub resource_backoff: HashMap<FlightId, (u32, DateTime<Utc>)>,
+
+    /// How often (in simulated time) `run_model` emits a `ModelEventType::DispatcherStats`
+    /// snapshot and resets `stats`.
+    pub stats_interval: TimeDelta,
+    /// Counters accumulated since the last `DispatcherStats` snapshot (or since the simulation
+    /// started, for the first one). `run_model` resets this after each emission.
+    pub stats: DispatcherStats,
+    /// Simulated time of the next scheduled `DispatcherStats` emission; set from `model.now()` at
+    /// the start of `run_model`.
+    pub next_stats_emit: Option<DateTime<Utc>>,
 }
 
 macro_rules! send_event {
@@ -122,6 +187,29 @@ impl Dispatcher {
         }
     }
 
+    /// Like `init_flight_updates`, but for a model partly realized already by an observed feed
+    /// (e.g. `adsb::AdsbFeedDriver`): a flight that has already arrived is left alone entirely, one
+    /// that has already taken off is enqueued straight to `CheckArrive`, and everything else still
+    /// starts at `CheckDepart` as usual.
+    pub fn init_flight_updates_from_observed(&mut self) {
+        for flight in self.model.flights.values() {
+            let flight = flight.read().unwrap();
+            if flight.arrive_time.is_some() {
+                continue;
+            }
+            let (time, update_type) = if flight.took_off() {
+                (flight.act_arrive_time(), UpdateType::CheckArrive)
+            } else {
+                (flight.sched_depart, UpdateType::CheckDepart)
+            };
+            self.update_queue.push(DispatcherUpdate {
+                time,
+                flight: flight.id,
+                _type: update_type,
+            });
+        }
+    }
+
     /// Run the entire network model by successively processing updates
     /// and sending out ModelEvents.
     ///
@@ -132,6 +220,7 @@ impl Dispatcher {
             self.model,
             ModelEventType::SimulationStarted(Arc::downgrade(&self.model))
         );
+        self.next_stats_emit = Some(self.model.now() + self.stats_interval);
 
         while let Some(update) = self.update_queue.pop() {
             // if update.time > self.model.end {
@@ -140,14 +229,44 @@ impl Dispatcher {
             {
                 *self.model._now.write().unwrap() = update.time;
             }
-            self.update_flight(update);
+            if self.batch_assign {
+                let mut batch = vec![update];
+                while self.update_queue.peek().map(|u| u.time) == Some(batch[0].time) {
+                    batch.push(self.update_queue.pop().unwrap());
+                }
+                self.batch_assign_aircraft(&batch);
+                for update in batch {
+                    self.update_flight(update);
+                }
+            } else {
+                self.update_flight(update);
+            }
+            self.maybe_emit_stats();
         }
 
         // for update in &self.update_queue {
         //     self.model.cancel_flight(update.flight, CancelReason::DelayTimedOut);
         // }
+        self.emit_stats();
         send_event!(self.model, ModelEventType::SimulationComplete);
     }
+
+    /// If `model.now()` has reached `next_stats_emit`, emit the accumulated `DispatcherStats` and
+    /// schedule the next one; otherwise a no-op.
+    fn maybe_emit_stats(&mut self) {
+        if self.next_stats_emit.map(|t| self.model.now() >= t).unwrap_or(false) {
+            self.emit_stats();
+            self.next_stats_emit = Some(self.model.now() + self.stats_interval);
+        }
+    }
+
+    /// Emit the counters accumulated in `self.stats` as a `ModelEventType::DispatcherStats`
+    /// snapshot, then reset them for the next interval.
+    fn emit_stats(&mut self) {
+        self.stats.queue_depth = self.update_queue.len();
+        let snapshot = std::mem::take(&mut self.stats);
+        send_event!(self.model, ModelEventType::DispatcherStats(snapshot));
+    }
     /// Check the status of the given `flight`.
     /// If possible, move its progress forward.
     ///
@@ -162,6 +281,7 @@ impl Dispatcher {
     ///   - The scheduled time enroute has elapsed since departure
     ///   - Landing clearance is given by all Disruptions
     pub fn update_flight(&mut self, update: DispatcherUpdate) {
+        self.stats.updates_processed += 1;
         match update._type {
             UpdateType::CheckDepart => {
                 {
@@ -176,6 +296,22 @@ impl Dispatcher {
                         return;
                     }
                 }
+                // Does the conflict graph say a higher-priority flight still holds a resource
+                // (tail or crew member) this flight would otherwise contend for?
+                if let Some(graph) = &self.conflict_graph {
+                    if let Some(blocker) = graph.blocking_predecessor(update.flight) {
+                        let retry_at = std::cmp::max(
+                            self.model.now() + RESOURCE_WAIT,
+                            self.model.flight_read(blocker).sched_depart,
+                        );
+                        self.update_queue.push(DispatcherUpdate {
+                            flight: update.flight,
+                            time: retry_at,
+                            _type: UpdateType::CheckDepart,
+                        });
+                        return;
+                    }
+                }
                 // Is there an assigned aircraft, and is the assigned aircraft available?
                 {
                     let flt = self.model.flight_read(update.flight);
@@ -218,6 +354,7 @@ impl Dispatcher {
                                     let reassigned = flt.reassign_aircraft(aircraft.clone());
                                     self.aircraft_reassigned.insert(flight);
                                     if reassigned {
+                                        self.stats.aircraft_reassignments += 1;
                                         send_event!(
                                             self.model,
                                             ModelEventType::AircraftAssignmentChanged(
@@ -232,6 +369,7 @@ impl Dispatcher {
                                     let flt = self.model.flight_read(update.flight);
                                     assert_eq!(flt.aircraft_tail, Some(ac.clone()));
                                     self.model.fleet[&ac].write().unwrap().claim(flt.id);
+                                    self.stats.aircraft_reassignments += 1;
                                     send_event!(
                                         self.model,
                                         ModelEventType::AircraftAssignmentChanged(
@@ -248,31 +386,27 @@ impl Dispatcher {
                                 } else {
                                     // Keep waiting. Maybe it can have a reassignment later
                                     // TODO make it possible to configure whether to cancel here
-                                    self.delay_departure(
+                                    self.delay_or_cancel_for_resource(
                                         self.model.now(),
                                         update.flight,
-                                        vec![(
-                                            RESOURCE_WAIT,
-                                            DelayReason::AircraftShortage(original_acft),
-                                        )],
+                                        DelayReason::AircraftShortage(original_acft),
                                     );
                                 }
                                 return;
                             } else {
                                 drop(flt);
-                                self.delay_departure(
+                                self.delay_or_cancel_for_resource(
                                     self.model.now(),
                                     update.flight,
-                                    vec![(
-                                        RESOURCE_WAIT,
-                                        DelayReason::AircraftShortage(original_acft),
-                                    )],
+                                    DelayReason::AircraftShortage(original_acft),
                                 );
                                 return;
                             }
                         } else {
                             // Can't deviate, must wait
-                            // Use the fallback selector: Pick the aircraft that will be able to serve this flight the earliest
+                            // Use the fallback selector: Pick the cheapest feasible aircraft (insertion
+                            // cost), not merely the one available earliest.
+                            self.stats.fallback_selector_invocations += 1;
                             drop(flt); // Switch to a read so that Aircraft::available_time doesn't cause a deadlock
                             let flt = self.model.flight_read(update.flight);
                             send_event!(
@@ -283,7 +417,7 @@ impl Dispatcher {
                                 )
                             );
                             // TODO consider incoming flights
-                            let aircraft_cands: Vec<(String, DateTime<Utc>)> =
+                            let aircraft_cands: Vec<(String, DateTime<Utc>, f64)> =
                                 if self.use_fallback_aircraft_selector {
                                     self.model
                                         .airports
@@ -294,57 +428,66 @@ impl Dispatcher {
                                         .fleet
                                         .iter()
                                         .filter_map(|aircraft_id| {
-                                            let avail = self
+                                            let aircraft = self
                                                 .model
                                                 .fleet
                                                 .get(aircraft_id)
                                                 .unwrap()
                                                 .read()
-                                                .unwrap()
-                                                .available_time(&self.model, &flt);
-                                            avail.map(|i| (aircraft_id.clone(), i))
+                                                .unwrap();
+                                            let avail = aircraft.available_time(&self.model, &flt)?;
+                                            let cost = assignment_cost::insertion_cost(
+                                                &self.model,
+                                                &flt,
+                                                avail,
+                                                aircraft.location,
+                                                assignment_cost::Resource::Aircraft(aircraft_id),
+                                            );
+                                            Some((aircraft_id.clone(), avail, cost))
                                         })
                                         .collect()
                                 } else {
                                     Vec::new()
                                 };
                             drop(flt);
-                            let (new_acft, delay_duration): (Option<String>, Option<TimeDelta>) =
-                                if aircraft_cands.is_empty() {
-                                    (None, Some(RESOURCE_WAIT))
-                                } else {
-                                    let selected_aircraft =
-                                        aircraft_cands.into_iter().min_by_key(|i| i.1).unwrap();
-                                    let mut flt = self.model.flight_write(update.flight);
-                                    flt.reassign_aircraft(selected_aircraft.0.clone());
-                                    {
-                                        self.model.fleet[&selected_aircraft.0]
-                                            .write()
-                                            .unwrap()
-                                            .claim(flt.id);
-                                    }
-                                    send_event!(
-                                        self.model,
-                                        ModelEventType::AircraftAssignmentChanged(
-                                            flt.id,
-                                            selected_aircraft.0.clone()
-                                        )
-                                    );
-                                    (
-                                        Some(selected_aircraft.0),
-                                        if selected_aircraft.1 <= self.model.now() {
-                                            None
-                                        } else {
-                                            Some(selected_aircraft.1 - self.model.now())
-                                        },
-                                    )
-                                };
-
-                            if let Some(delay_duration) = delay_duration {
+                            if aircraft_cands.is_empty() {
+                                self.delay_or_cancel_for_resource(
+                                    self.model.now(),
+                                    update.flight,
+                                    DelayReason::AircraftShortage(None),
+                                );
+                                return;
+                            }
+                            let selected_aircraft = aircraft_cands
+                                .into_iter()
+                                .min_by(|a, b| a.2.total_cmp(&b.2))
+                                .map(|(tail, avail, _)| (tail, avail))
+                                .unwrap();
+                            let mut flt = self.model.flight_write(update.flight);
+                            flt.reassign_aircraft(selected_aircraft.0.clone());
+                            {
+                                self.model.fleet[&selected_aircraft.0]
+                                    .write()
+                                    .unwrap()
+                                    .claim(flt.id);
+                            }
+                            self.stats.aircraft_reassignments += 1;
+                            send_event!(
+                                self.model,
+                                ModelEventType::AircraftAssignmentChanged(
+                                    flt.id,
+                                    selected_aircraft.0.clone()
+                                )
+                            );
+                            let new_acft = Some(selected_aircraft.0);
+                            if selected_aircraft.1 > self.model.now() {
                                 self.delay_departure(
                                     self.model.now(),
                                     update.flight,
-                                    vec![(delay_duration, DelayReason::AircraftShortage(new_acft))],
+                                    vec![(
+                                        selected_aircraft.1 - self.model.now(),
+                                        DelayReason::AircraftShortage(new_acft),
+                                    )],
                                 );
                                 return;
                             }
@@ -423,6 +566,7 @@ impl Dispatcher {
                                 for crew in &crews {
                                     self.model.crew[crew].write().unwrap().claim(flt.id);
                                 }
+                                self.stats.crew_reassignments += 1;
                                 send_event!(
                                     self.model,
                                     ModelEventType::CrewAssignmentChanged(flt.id, crews)
@@ -435,18 +579,24 @@ impl Dispatcher {
                                 return;
                             } else {
                                 // No reassignment, must cancel
+                                let illegal_crew = needs_reassignment
+                                    .iter()
+                                    .find(|id| self.crew_illegal(**id, &flt))
+                                    .copied();
                                 drop(flt);
+                                let reason = match illegal_crew {
+                                    Some(id) => DelayReason::CrewIllegal(id),
+                                    None => DelayReason::CrewShortage(needs_reassignment),
+                                };
                                 self.cancel_flight(
                                     update.flight,
-                                    CancelReason::HeavyExpectedDelay(DelayReason::CrewShortage(
-                                        needs_reassignment,
-                                    )),
+                                    CancelReason::HeavyExpectedDelay(reason),
                                 );
                                 return;
                             }
                         } else {
                             // No crew selector, just wait
-                            let mut delay_decision = RESOURCE_WAIT;
+                            let mut delay_decision: Option<TimeDelta> = None;
                             let mut delay_cause: Option<Vec<CrewId>> = None;
                             if flt.crew.is_empty()
                                 && !self.model.airports[&flt.origin]
@@ -455,28 +605,34 @@ impl Dispatcher {
                                     .crew
                                     .is_empty()
                             {
-                                // Fallback selector: Pick the crew that can take this flight most immediately
+                                // Fallback selector: Pick the cheapest feasible crew (insertion cost),
+                                // not merely the one available most immediately.
                                 let arpt = self.model.airports[&flt.origin].read().unwrap();
                                 let best_crew = arpt
                                     .crew
                                     .iter()
-                                    .map(|id| {
+                                    .filter_map(|id| {
                                         let crew = self.model.crew[id].read().unwrap();
-                                        (
-                                            id,
-                                            crew.time_until_available_for(
-                                                &flt,
-                                                self.model.now(),
-                                                &self.model,
-                                            ),
-                                        )
+                                        let wait_time = crew.time_until_available_for(
+                                            &flt,
+                                            self.model.now(),
+                                            &self.model,
+                                        )?;
+                                        let cost = assignment_cost::insertion_cost(
+                                            &self.model,
+                                            &flt,
+                                            self.model.now() + wait_time,
+                                            crew.location,
+                                            assignment_cost::Resource::Crew(*id),
+                                        );
+                                        Some((id, wait_time, cost))
                                     })
-                                    .filter(|i| i.1.is_some())
-                                    .map(|i| (i.0, i.1.unwrap()))
-                                    .min_by_key(|i| i.1);
+                                    .min_by(|a, b| a.2.total_cmp(&b.2))
+                                    .map(|(id, wait_time, _)| (id, wait_time));
                                 if let Some((best_id, wait_time)) = best_crew {
                                     flt.reassign_crew(vec![*best_id]);
                                     self.model.crew[best_id].write().unwrap().claim(flt.id);
+                                    self.stats.crew_reassignments += 1;
                                     send_event!(
                                         self.model,
                                         ModelEventType::CrewAssignmentChanged(
@@ -492,21 +648,41 @@ impl Dispatcher {
                                         });
                                         return;
                                     }
-                                    delay_decision = wait_time;
+                                    delay_decision = Some(wait_time);
                                     delay_cause = Some(vec![*best_id]);
                                 }
                             }
+                            let illegal_crew = if delay_decision.is_none() {
+                                needs_reassignment
+                                    .iter()
+                                    .find(|id| self.crew_illegal(**id, &flt))
+                                    .copied()
+                            } else {
+                                None
+                            };
                             drop(flt);
-                            self.delay_departure(
-                                self.model.now(),
-                                update.flight,
-                                vec![(
-                                    delay_decision,
-                                    DelayReason::CrewShortage(
-                                        delay_cause.unwrap_or(needs_reassignment),
-                                    ),
-                                )],
-                            );
+                            let cause = match illegal_crew {
+                                Some(id) => DelayReason::CrewIllegal(id),
+                                None => DelayReason::CrewShortage(
+                                    delay_cause.unwrap_or(needs_reassignment),
+                                ),
+                            };
+                            match delay_decision {
+                                Some(wait) => {
+                                    self.delay_departure(
+                                        self.model.now(),
+                                        update.flight,
+                                        vec![(wait, cause)],
+                                    );
+                                }
+                                None => {
+                                    self.delay_or_cancel_for_resource(
+                                        self.model.now(),
+                                        update.flight,
+                                        cause,
+                                    );
+                                }
+                            }
                             return;
                         }
                     } else {
@@ -677,6 +853,133 @@ impl Dispatcher {
         }
     }
 
+    /// Ballista-style "task-first" assignment: rather than letting each due flight in `batch`
+    /// independently claim the earliest aircraft it can reach in whatever order `update_queue`
+    /// happens to pop them (today's behavior, where the first flight processed can grab a scarce
+    /// tail a later-but-more-critical flight needed more), collect every `CheckDepart` update in
+    /// `batch` that currently lacks a usable aircraft, rank them by `batch_assign_cost`, and hand
+    /// each its earliest-available compatible aircraft in that order, removing the assignment
+    /// from the pool (via `Aircraft::claim`) before considering the next flight.
+    fn batch_assign_aircraft(&mut self, batch: &[DispatcherUpdate]) {
+        if self.aircraft_selector.is_some() || !self.use_fallback_aircraft_selector {
+            return;
+        }
+        let now = self.model.now();
+        let mut unmet: Vec<FlightId> = batch
+            .iter()
+            .filter(|u| matches!(u._type, UpdateType::CheckDepart))
+            .map(|u| u.flight)
+            .filter(|&id| {
+                let flt = self.model.flight_read(id);
+                if flt.sched_depart > now || flt.cancelled || flt.took_off() {
+                    return false;
+                }
+                let ac_avail = flt.aircraft_tail.as_ref().and_then(|tail| {
+                    self.model.fleet[tail]
+                        .read()
+                        .unwrap()
+                        .available_time(&self.model, &flt)
+                });
+                ac_avail
+                    .map(|d| d > now + self.aircraft_tolerance_before_reassign)
+                    .unwrap_or(true)
+            })
+            .collect();
+        if unmet.is_empty() {
+            return;
+        }
+        self.stats.fallback_selector_invocations += 1;
+        unmet.sort_by(|&a, &b| {
+            (self.batch_assign_cost)(&self.model, a)
+                .partial_cmp(&(self.batch_assign_cost)(&self.model, b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        for flight_id in unmet {
+            let flt = self.model.flight_read(flight_id);
+            let origin = flt.origin;
+            let candidate = self.model.airports[&origin]
+                .read()
+                .unwrap()
+                .fleet
+                .iter()
+                .filter_map(|tail| {
+                    let aircraft = self.model.fleet[tail].read().unwrap();
+                    aircraft
+                        .available_time(&self.model, &flt)
+                        .map(|avail| (tail.clone(), avail))
+                })
+                .min_by_key(|(_, avail)| *avail);
+            drop(flt);
+
+            let Some((tail, _)) = candidate else {
+                continue;
+            };
+            self.model.flight_write(flight_id).reassign_aircraft(tail.clone());
+            self.model.fleet[&tail].write().unwrap().claim(flight_id);
+            self.stats.aircraft_reassignments += 1;
+            send_event!(
+                self.model,
+                ModelEventType::AircraftAssignmentChanged(flight_id, tail)
+            );
+        }
+    }
+
+    /// Compute the next re-check delay for `flight` when it currently has no usable aircraft/crew
+    /// candidate at all. Doubles `resource_wait_base` once per consecutive empty attempt, capped
+    /// at `resource_wait_cap`. Returns `None` once `flight` has been backing off for longer than
+    /// `max_resource_wait`, in which case the caller should cancel it instead of requeuing it yet
+    /// again.
+    fn next_resource_wait(&mut self, flight: FlightId) -> Option<TimeDelta> {
+        let now = self.model.now();
+        let (attempts, first_wait) = *self
+            .resource_backoff
+            .entry(flight)
+            .or_insert((0, now));
+        if now - first_wait > self.max_resource_wait {
+            self.resource_backoff.remove(&flight);
+            return None;
+        }
+        let mut wait = self.resource_wait_base;
+        for _ in 0..attempts {
+            if wait >= self.resource_wait_cap {
+                break;
+            }
+            wait = std::cmp::min(wait * 2, self.resource_wait_cap);
+        }
+        self.resource_backoff.insert(flight, (attempts + 1, first_wait));
+        if attempts > 0 {
+            send_event!(
+                self.model,
+                ModelEventType::ResourceWaitEscalated(flight, attempts + 1, wait)
+            );
+        }
+        Some(wait)
+    }
+
+    /// True if `crew_id` could never legally operate `flt` under `model.config.crew_duty_engine`,
+    /// as opposed to merely being tied up or away from the right airport right now.
+    fn crew_illegal(&self, crew_id: CrewId, flt: &Flight) -> bool {
+        !self.model.crew[&crew_id]
+            .read()
+            .unwrap()
+            .legal_for(flt, &self.model)
+    }
+
+    /// Delay `flight` using the backoff schedule from `next_resource_wait`, or cancel it with
+    /// `CancelReason::DelayTimedOut` once that backoff has run out the clock on `max_resource_wait`.
+    fn delay_or_cancel_for_resource(
+        &mut self,
+        now: DateTime<Utc>,
+        flight: FlightId,
+        reason: DelayReason,
+    ) {
+        match self.next_resource_wait(flight) {
+            Some(wait) => self.delay_departure(now, flight, vec![(wait, reason)]),
+            None => self.cancel_flight(flight, CancelReason::DelayTimedOut),
+        }
+    }
+
     fn delay_departure(
         &mut self,
         now: DateTime<Utc>,
@@ -694,6 +997,7 @@ impl Dispatcher {
             }
         }
         for comp in reason {
+            *self.stats.delayed.entry(comp.1.clone()).or_insert(0) += 1;
             send_event!(
                 self.model,
                 ModelEventType::FlightDepartureDelayed(id, comp.0, comp.1)
@@ -708,6 +1012,7 @@ impl Dispatcher {
     }
 
     fn cancel_flight(&mut self, flight: FlightId, reason: CancelReason) {
+        *self.stats.cancelled.entry(reason.clone()).or_insert(0) += 1;
         self.model.cancel_flight(flight, reason);
         if let Some(ref mut selector) = self.aircraft_selector {
             selector.on_flight_cancel(flight, &self.model);
@@ -715,9 +1020,14 @@ impl Dispatcher {
         if let Some(ref mut selector) = self.crew_selector {
             selector.on_flight_cancel(flight, &self.model);
         }
+        if let Some(ref mut graph) = self.conflict_graph {
+            graph.resolve(flight);
+        }
+        self.resource_backoff.remove(&flight);
     }
 
     fn depart_flight(&mut self, id: FlightId) {
+        self.stats.departed += 1;
         self.model.depart_flight(id);
         if let Some(ref mut selector) = self.aircraft_selector {
             selector.on_flight_depart(id, &self.model);
@@ -725,6 +1035,10 @@ impl Dispatcher {
         if let Some(ref mut selector) = self.crew_selector {
             selector.on_flight_depart(id, &self.model);
         }
+        if let Some(ref mut graph) = self.conflict_graph {
+            graph.resolve(id);
+        }
+        self.resource_backoff.remove(&id);
     }
 }
 
@@ -758,20 +1072,46 @@ pub mod strategies {
         surplus_aircraft: Vec<(DateTime<Utc>, String, AirportCode)>,
         unfulfilled: HashMap<AirportCode, Vec<FlightId>>,
         cached_reservations: Option<HashMap<FlightId, String>>,
+        cached_ferry_legs: Vec<FerryLeg>,
         last_ran: Option<DateTime<Utc>>,
+        objective: ObjectiveKind,
+        /// Cap on how many synthetic repositioning legs (and how many cumulative hours of them)
+        /// a single surplus aircraft may fly within one `run_dfs` path, so a search can't ferry
+        /// an aircraft across the whole network chasing ever more distant unfulfilled flights.
+        max_ferry_legs: u32,
+        max_ferry_duration: TimeDelta,
     }
 
     impl DfsAircraftSelectionStrategy {
-        pub fn new() -> Self {
+        pub fn new(
+            objective: ObjectiveKind,
+            max_ferry_legs: u32,
+            max_ferry_duration: TimeDelta,
+        ) -> Self {
             Self {
                 surplus_aircraft: Vec::new(),
                 unfulfilled: HashMap::new(),
                 cached_reservations: None,
+                cached_ferry_legs: Vec::new(),
                 last_ran: None,
+                objective,
+                max_ferry_legs,
+                max_ferry_duration,
             }
         }
 
-        fn run_dfs(&self, model: &Model) -> HashMap<FlightId, String> {
+        /// A typical flight time between `from` and `to`, stood in for a real distance/route
+        /// table: the duration of any scheduled flight already flying that station pair. `None`
+        /// if the network has no such route, in which case a ferry there can't be costed out and
+        /// is skipped.
+        fn ferry_duration(model: &Model, from: AirportCode, to: AirportCode) -> Option<TimeDelta> {
+            model.flights.values().find_map(|f| {
+                let flt = f.try_read().ok()?;
+                (flt.origin == from && flt.dest == to).then(|| flt.est_duration())
+            })
+        }
+
+        fn run_dfs(&self, model: &Model) -> (HashMap<FlightId, String>, Vec<FerryLeg>) {
             println!(
                 "DFS debug: There are {} surplus aircraft and {} unfulfilled flights",
                 self.surplus_aircraft.len(),
@@ -783,9 +1123,27 @@ pub mod strategies {
                 location: AirportCode,
                 next_available: DateTime<Utc>,
                 accum_delay: TimeDelta,
+                weighted_delay_minutes: f64,
+                latest_arrival: DateTime<Utc>,
+                /// Repositioning hops taken to reach `location`, in order: `(origin, dest,
+                /// depart, duration)`. Kept separate from `trail` since these aren't real
+                /// flights with a `FlightId`.
+                ferry_hops: Vec<(AirportCode, AirportCode, DateTime<Utc>, TimeDelta)>,
+            }
+
+            impl Node {
+                fn cost(&self, objective: ObjectiveKind) -> f64 {
+                    objective.cost(
+                        self.trail.len(),
+                        self.accum_delay,
+                        self.weighted_delay_minutes,
+                        self.latest_arrival,
+                    )
+                }
             }
 
             let mut reservations: HashMap<FlightId, String> = HashMap::new();
+            let mut ferry_legs: Vec<FerryLeg> = Vec::new();
             let mut num_aircraft_with_path = 0u32;
             for (start_time, aircraft, origin) in &self.surplus_aircraft {
                 let mut frontier: Vec<Node> = vec![Node {
@@ -793,22 +1151,25 @@ pub mod strategies {
                     location: *origin,
                     next_available: *start_time,
                     accum_delay: TimeDelta::zero(),
+                    weighted_delay_minutes: 0.0,
+                    latest_arrival: *start_time,
+                    ferry_hops: Vec::new(),
                 }];
-                let mut longest: Option<Node> = None;
+                let mut best: Option<Node> = None;
                 while let Some(node) = frontier.pop() {
                     // println!("searching for {}: {:?}", aircraft, &node);
-                    if longest.is_none()
-                        || longest.as_ref().unwrap().trail.len() < node.trail.len()
-                        || (longest.as_ref().unwrap().trail.len() == node.trail.len()
-                            && longest.as_ref().unwrap().accum_delay > node.accum_delay)
+                    if best.is_none() || best.as_ref().unwrap().cost(self.objective) > node.cost(self.objective)
                     {
-                        longest = Some(node.clone());
+                        best = Some(node.clone());
                     }
                     let Node {
                         trail,
                         location,
                         next_available,
                         accum_delay,
+                        weighted_delay_minutes,
+                        latest_arrival,
+                        ferry_hops,
                     } = node;
                     if trail.len() > 4 {
                         continue;
@@ -838,6 +1199,49 @@ pub mod strategies {
                     } else {
                         Vec::new()
                     };
+                    if next.is_empty() {
+                        // Nothing flyable from here: consider ferrying empty to another station
+                        // with unfulfilled flights, if the aircraft has ferry budget left.
+                        let ferry_duration_used = ferry_hops
+                            .iter()
+                            .map(|(_, _, _, d)| *d)
+                            .fold(TimeDelta::zero(), |a, b| a + b);
+                        if (ferry_hops.len() as u32) < self.max_ferry_legs {
+                            for (&dest, flights) in &self.unfulfilled {
+                                if dest == location
+                                    || flights.iter().all(|f| reservations.contains_key(f))
+                                {
+                                    continue;
+                                }
+                                let Some(duration) = Self::ferry_duration(model, location, dest)
+                                else {
+                                    continue;
+                                };
+                                if ferry_duration_used + duration > self.max_ferry_duration {
+                                    continue;
+                                }
+                                let mut next_ferry_hops = ferry_hops.clone();
+                                next_ferry_hops.push((location, dest, next_available, duration));
+                                frontier.push(Node {
+                                    trail: trail.clone(),
+                                    location: dest,
+                                    next_available: next_available
+                                        + duration
+                                        + model.config.aircraft_turnaround_time,
+                                    // Ferry time is pure repositioning cost: charged the same as
+                                    // aircraft-shortage delay so the search only pays for it when
+                                    // it unlocks enough downstream coverage to be worth it.
+                                    accum_delay: accum_delay + duration,
+                                    weighted_delay_minutes,
+                                    latest_arrival: std::cmp::max(
+                                        latest_arrival,
+                                        next_available + duration,
+                                    ),
+                                    ferry_hops: next_ferry_hops,
+                                });
+                            }
+                        }
+                    }
                     for next_flight in next {
                         let flight_info = model.flight_read(next_flight);
                         let depart_time = std::cmp::max(
@@ -849,31 +1253,44 @@ pub mod strategies {
                             depart_time - (flight_info.sched_depart + flight_info.dep_delay);
                         let mut next_trail = trail.clone();
                         next_trail.push(next_flight);
-                        let time_available_after = depart_time
-                            + flight_info.est_duration()
-                            + model.config.aircraft_turnaround_time;
+                        let arrival_time = depart_time + flight_info.est_duration();
+                        let time_available_after =
+                            arrival_time + model.config.aircraft_turnaround_time;
                         frontier.push(Node {
                             trail: next_trail,
                             location: flight_info.dest,
                             next_available: time_available_after,
                             accum_delay: accum_delay + delay,
+                            weighted_delay_minutes: weighted_delay_minutes
+                                + delay.num_minutes() as f64 * passenger_load(&flight_info),
+                            latest_arrival: std::cmp::max(latest_arrival, arrival_time),
+                            ferry_hops: ferry_hops.clone(),
                         });
                     }
                 }
-                if let Some(longest) = longest {
+                if let Some(best) = best {
                     println!(
                         "DFS resolved: Path for {} (currently at {}) should be {:?}",
-                        aircraft, origin, longest
+                        aircraft, origin, best
                     );
-                    if !longest.trail.is_empty() {
+                    if !best.trail.is_empty() {
                         num_aircraft_with_path += 1;
                     }
-                    for flight in longest.trail {
+                    for (hop_origin, hop_dest, depart, duration) in best.ferry_hops {
+                        ferry_legs.push(FerryLeg {
+                            aircraft: aircraft.clone(),
+                            origin: hop_origin,
+                            dest: hop_dest,
+                            depart,
+                            duration,
+                        });
+                    }
+                    for flight in best.trail {
                         reservations.insert(flight, aircraft.clone());
                     }
                 }
             }
-            
+
             println!(
                 "[[DFS STATS]] [{}, {}, {}, {}, {}]",
                 model.now(),
@@ -882,7 +1299,7 @@ pub mod strategies {
                 self.surplus_aircraft.len(),
                 num_aircraft_with_path
             );
-            reservations
+            (reservations, ferry_legs)
         }
 
         fn remove_stale_flights(&mut self, model: &Model) {
@@ -901,8 +1318,9 @@ pub mod strategies {
                 || self.last_ran.unwrap() < _model.now() - TimeDelta::minutes(15)
             {
                 self.remove_stale_flights(_model);
-                let reservations = self.run_dfs(_model);
+                let (reservations, ferry_legs) = self.run_dfs(_model);
                 self.cached_reservations = Some(reservations);
+                self.cached_ferry_legs = ferry_legs;
                 self.last_ran = Some(_model.now());
             }
             // println!("DFS output: {:?}", self.cached_reservations);
@@ -920,6 +1338,10 @@ pub mod strategies {
                 .clone()
         }
 
+        fn ferry_legs(&self, _model: &Model) -> Vec<FerryLeg> {
+            self.cached_ferry_legs.clone()
+        }
+
         fn on_flight_cancel(&mut self, flight: FlightId, _model: &Model) {
             // Available assigned aircraft --> surplus aircraft
             let flight = _model.flight_read(flight);
@@ -975,17 +1397,794 @@ pub mod strategies {
         }
     }
 
-    pub fn new_for_aircraft(key: &str) -> Box<dyn AircraftSelectionStrategy> {
+    /// Reward added per flight covered by a trail, dwarfing any realistic `accum_delay` (in
+    /// minutes) so the search always prefers covering one more flight over saving any amount of
+    /// delay; only ties among equal-coverage trails are broken by delay.
+    const COVERAGE_REWARD: f64 = 1_000_000.0;
+
+    /// Selects what a search backend's node/chain scoring actually optimizes for, mirroring how a
+    /// routing engine lets the caller pick minimize-cost vs. minimize-arrival-time as the global
+    /// objective instead of hardcoding one.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum ObjectiveKind {
+        /// The long-standing behavior: prefer the trail covering the most flights, breaking ties
+        /// by lower aircraft-shortage delay.
+        MaximizeCoverage,
+        /// Prefer the trail whose covered legs cost the fewest passenger-minutes of aircraft-
+        /// shortage delay, weighting each leg's delay by the passenger load it's carrying.
+        MinimizeTotalArrivalDelay,
+        /// Prefer the trail that finishes all its recovered legs earliest, scored by the latest
+        /// `depart_time + est_duration` across the trail.
+        MinimizeLatestArrival,
+    }
+
+    impl ObjectiveKind {
+        fn parse(key: &str) -> Self {
+            match key {
+                "coverage" => ObjectiveKind::MaximizeCoverage,
+                "total_delay" => ObjectiveKind::MinimizeTotalArrivalDelay,
+                "latest_arrival" => ObjectiveKind::MinimizeLatestArrival,
+                _ => unimplemented!("aircraft search objective {:?}", key),
+            }
+        }
+
+        /// Lower is always better under every objective, so every search backend in this module
+        /// can rank partial/complete trails with a single `<` comparison regardless of which
+        /// objective is active.
+        fn cost(
+            &self,
+            trail_len: usize,
+            accum_delay: TimeDelta,
+            weighted_delay_minutes: f64,
+            latest_arrival: DateTime<Utc>,
+        ) -> f64 {
+            match self {
+                ObjectiveKind::MaximizeCoverage => {
+                    accum_delay.num_seconds() as f64 / 60.0 - trail_len as f64 * COVERAGE_REWARD
+                }
+                ObjectiveKind::MinimizeTotalArrivalDelay => weighted_delay_minutes,
+                ObjectiveKind::MinimizeLatestArrival => latest_arrival.timestamp() as f64,
+            }
+        }
+    }
+
+    /// Passenger count aboard `flight`, i.e. the weight `MinimizeTotalArrivalDelay` charges that
+    /// leg's aircraft-shortage delay by.
+    fn passenger_load(flight: &Flight) -> f64 {
+        flight.passengers.iter().map(|demand| demand.count).sum::<u32>() as f64
+    }
+
+    #[derive(Clone, Debug)]
+    struct SearchNode {
+        trail: Vec<FlightId>,
+        location: AirportCode,
+        next_available: DateTime<Utc>,
+        accum_delay: TimeDelta,
+        weighted_delay_minutes: f64,
+        latest_arrival: DateTime<Utc>,
+        depth: u32,
+    }
+
+    impl SearchNode {
+        /// `g`: the real cost of this trail so far under `objective` (lower is better).
+        fn g(&self, objective: ObjectiveKind) -> f64 {
+            objective.cost(
+                self.trail.len(),
+                self.accum_delay,
+                self.weighted_delay_minutes,
+                self.latest_arrival,
+            )
+        }
+    }
+
+    /// A node on the binary-heap frontier, ordered by ascending `f = g + h` so `BinaryHeap`
+    /// (a max-heap) pops the lowest-cost node first, the same inversion `DispatcherUpdate` uses
+    /// for its time-ordered heap.
+    struct ScoredNode {
+        f: f64,
+        node: SearchNode,
+    }
+    impl PartialEq for ScoredNode {
+        fn eq(&self, other: &Self) -> bool {
+            self.f == other.f
+        }
+    }
+    impl Eq for ScoredNode {}
+    impl PartialOrd for ScoredNode {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for ScoredNode {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            other.f.partial_cmp(&self.f).unwrap_or(std::cmp::Ordering::Equal)
+        }
+    }
+
+    /// Best-first / beam-search replacement for `DfsAircraftSelectionStrategy`'s depth-4-capped
+    /// LIFO DFS. Shares the same surplus/unfulfilled bookkeeping shape, but explores successors
+    /// ordered by `f = g + h` off a binary-heap frontier, keeping only the `beam_width` lowest-`f`
+    /// nodes at each expansion depth (`"beam"`) so it can search past 4 legs without the frontier
+    /// growing unbounded; `"astar"` sets `beam_width` to `u32::MAX` to explore exhaustively up to
+    /// `max_depth` instead.
+    struct GraphSearchAircraftSelectionStrategy {
+        surplus_aircraft: Vec<(DateTime<Utc>, String, AirportCode)>,
+        unfulfilled: HashMap<AirportCode, Vec<FlightId>>,
+        cached_reservations: Option<HashMap<FlightId, String>>,
+        last_ran: Option<DateTime<Utc>>,
+        beam_width: u32,
+        max_depth: u32,
+        objective: ObjectiveKind,
+    }
+
+    impl GraphSearchAircraftSelectionStrategy {
+        fn new(beam_width: u32, max_depth: u32, objective: ObjectiveKind) -> Self {
+            Self {
+                surplus_aircraft: Vec::new(),
+                unfulfilled: HashMap::new(),
+                cached_reservations: None,
+                last_ran: None,
+                beam_width,
+                max_depth,
+                objective,
+            }
+        }
+
+        /// Expand `node` into one successor per still-reachable, unreserved flight departing
+        /// from its current `location`, applying the same feasibility window and reservation
+        /// check `DfsAircraftSelectionStrategy::run_dfs` uses.
+        fn successors(
+            &self,
+            model: &Model,
+            node: &SearchNode,
+            reservations: &HashMap<FlightId, String>,
+        ) -> Vec<SearchNode> {
+            let Some(flights) = self.unfulfilled.get(&node.location) else {
+                return Vec::new();
+            };
+            flights
+                .iter()
+                .filter(|f| !reservations.contains_key(*f) && !node.trail.contains(*f))
+                .filter_map(|flight_id| {
+                    let flight = model.flights[flight_id].try_read().ok()?;
+                    let feasible = flight.sched_depart + flight.dep_delay - node.next_available
+                        > TimeDelta::hours(-2)
+                        && node.next_available - flight.sched_depart < model.config.max_delay;
+                    if !feasible {
+                        return None;
+                    }
+                    let depart_time = std::cmp::max(
+                        flight.sched_depart + flight.dep_delay,
+                        node.next_available,
+                    );
+                    let delay = depart_time - (flight.sched_depart + flight.dep_delay);
+                    let arrival_time = depart_time + flight.est_duration();
+                    let mut trail = node.trail.clone();
+                    trail.push(*flight_id);
+                    Some(SearchNode {
+                        trail,
+                        location: flight.dest,
+                        next_available: arrival_time + model.config.aircraft_turnaround_time,
+                        accum_delay: node.accum_delay + delay,
+                        weighted_delay_minutes: node.weighted_delay_minutes
+                            + delay.num_minutes() as f64 * passenger_load(&flight),
+                        latest_arrival: std::cmp::max(node.latest_arrival, arrival_time),
+                        depth: node.depth + 1,
+                    })
+                })
+                .collect()
+        }
+
+        /// Estimate of additional flights coverable from `node`. It only counts still-unreserved
+        /// flights departing directly from `node.location` (ignoring further chains through other
+        /// airports, and whether those flights can be chained together), so it is neither a lower
+        /// nor an upper bound on the coverage actually reachable. It is capped by the remaining depth
+        /// budget so it never promises more coverage than `max_depth` allows. Only meaningful
+        /// under `MaximizeCoverage`, whose cost is denominated in coverage; the other objectives
+        /// get no lookahead (`h = 0`), falling back to plain best-first on `g`.
+        fn heuristic(
+            &self,
+            model: &Model,
+            node: &SearchNode,
+            reservations: &HashMap<FlightId, String>,
+        ) -> f64 {
+            if self.objective != ObjectiveKind::MaximizeCoverage {
+                return 0.0;
+            }
+            let remaining_depth = self.max_depth.saturating_sub(node.depth);
+            if remaining_depth == 0 {
+                return 0.0;
+            }
+            let reachable = self
+                .unfulfilled
+                .get(&node.location)
+                .map(|flights| {
+                    flights
+                        .iter()
+                        .filter(|f| !reservations.contains_key(*f) && !node.trail.contains(*f))
+                        .filter(|f| {
+                            model.flights[*f]
+                                .try_read()
+                                .map(|flight| {
+                                    flight.sched_depart + flight.dep_delay - node.next_available
+                                        > TimeDelta::hours(-2)
+                                        && node.next_available - flight.sched_depart
+                                            < model.config.max_delay
+                                })
+                                .unwrap_or(false)
+                        })
+                        .count() as u32
+                })
+                .unwrap_or(0);
+            -(reachable.min(remaining_depth) as f64) * COVERAGE_REWARD
+        }
+
+        fn run_search(&self, model: &Model) -> HashMap<FlightId, String> {
+            let mut reservations: HashMap<FlightId, String> = HashMap::new();
+            for (start_time, aircraft, origin) in &self.surplus_aircraft {
+                let root = SearchNode {
+                    trail: Vec::new(),
+                    location: *origin,
+                    next_available: *start_time,
+                    accum_delay: TimeDelta::zero(),
+                    weighted_delay_minutes: 0.0,
+                    latest_arrival: *start_time,
+                    depth: 0,
+                };
+                let mut best = root.clone();
+                let mut layer = vec![root];
+                while let Some(depth) = layer.first().map(|n| n.depth) {
+                    for node in &layer {
+                        if node.g(self.objective) < best.g(self.objective) {
+                            best = node.clone();
+                        }
+                    }
+                    if depth >= self.max_depth {
+                        break;
+                    }
+                    let mut heap: BinaryHeap<ScoredNode> = BinaryHeap::new();
+                    for node in &layer {
+                        for succ in self.successors(model, node, &reservations) {
+                            let f = succ.g(self.objective) + self.heuristic(model, &succ, &reservations);
+                            heap.push(ScoredNode { f, node: succ });
+                        }
+                    }
+                    if heap.is_empty() {
+                        break;
+                    }
+                    layer = (0..self.beam_width)
+                        .map_while(|_| heap.pop())
+                        .map(|scored| scored.node)
+                        .collect();
+                }
+                if !best.trail.is_empty() {
+                    for flight in best.trail {
+                        reservations.insert(flight, aircraft.clone());
+                    }
+                }
+            }
+            reservations
+        }
+
+        fn remove_stale_flights(&mut self, model: &Model) {
+            for (_, v) in self.unfulfilled.iter_mut() {
+                v.retain(|f| {
+                    let flt = model.flight_read(*f);
+                    flt.sched_depart > model.now() - TimeDelta::hours(4) && !flt.cancelled
+                });
+            }
+        }
+    }
+
+    impl AircraftSelectionStrategy for GraphSearchAircraftSelectionStrategy {
+        fn select(&mut self, flight: FlightId, model: &Model) -> Option<String> {
+            if self.last_ran.is_none() || self.last_ran.unwrap() < model.now() - TimeDelta::minutes(15)
+            {
+                self.remove_stale_flights(model);
+                self.cached_reservations = Some(self.run_search(model));
+                self.last_ran = Some(model.now());
+            }
+            self.cached_reservations.as_ref().unwrap().get(&flight).cloned()
+        }
+
+        fn reassign_suggestions(&self, _model: &Model) -> HashMap<FlightId, String> {
+            self.cached_reservations.as_ref().unwrap_or(&HashMap::new()).clone()
+        }
+
+        fn on_flight_cancel(&mut self, flight: FlightId, model: &Model) {
+            let flight = model.flight_read(flight);
+            if let Some(tail) = &flight.aircraft_tail {
+                let acft = model.fleet[tail].read().unwrap();
+                if let Some(avail_time) = acft.available_time(model, &flight) {
+                    self.surplus_aircraft.retain(|i| i.1 != *tail);
+                    let index = match self.surplus_aircraft.binary_search_by_key(&avail_time, |i| i.0)
+                    {
+                        Ok(n) => n,
+                        Err(n) => n,
+                    };
+                    self.surplus_aircraft
+                        .insert(index, (avail_time, tail.clone(), flight.origin));
+                }
+                for (id, v) in &model.flights {
+                    if let Ok(next) = v.read() {
+                        if next.aircraft_tail.as_ref() == Some(tail)
+                            && next.sched_depart > flight.sched_depart
+                            && !next.cancelled
+                        {
+                            self.unfulfilled.entry(next.origin).or_insert_with(Vec::new).push(*id);
+                        }
+                    }
+                }
+            }
+        }
+
+        fn on_flight_depart(&mut self, flight: FlightId, model: &Model) {
+            let flight = model.flight_read(flight);
+            let acft = flight
+                .aircraft_tail
+                .as_ref()
+                .expect("Departed flight must have assigned aircraft!");
+            if let Some(index) = self.surplus_aircraft.iter().position(|(_, v, _)| *v == *acft) {
+                self.surplus_aircraft.remove(index);
+            }
+            self.unfulfilled.entry(flight.origin).and_modify(|vec| {
+                if let Some(i) = vec.iter().position(|id| *id == flight.id) {
+                    vec.remove(i);
+                }
+            });
+        }
+    }
+
+    /// A single candidate chain a surplus aircraft could fly: the flights it would cover, in
+    /// order, and the total aircraft-shortage delay (minutes) incurred doing so.
+    struct Chain {
+        trail: Vec<FlightId>,
+        delay_minutes: f64,
+        weighted_delay_minutes: f64,
+        latest_arrival: DateTime<Utc>,
+    }
+
+    impl Chain {
+        /// `branch_and_bound` maximizes, so each arm is the negation of the equivalent
+        /// lower-is-better cost `ObjectiveKind::cost` uses elsewhere in this module.
+        /// `MaximizeCoverage` keeps the `COVERAGE_REWARD` trade-off
+        /// `GraphSearchAircraftSelectionStrategy` uses (flights covered dominate delay); the
+        /// other two just maximize the negative of their cost.
+        fn score(&self, objective: ObjectiveKind) -> f64 {
+            match objective {
+                ObjectiveKind::MaximizeCoverage => {
+                    self.trail.len() as f64 * COVERAGE_REWARD - self.delay_minutes
+                }
+                ObjectiveKind::MinimizeTotalArrivalDelay => -self.weighted_delay_minutes,
+                ObjectiveKind::MinimizeLatestArrival => -(self.latest_arrival.timestamp() as f64),
+            }
+        }
+    }
+
+    /// Optimal (exhaustive branch-and-bound) alternative to `DfsAircraftSelectionStrategy`'s
+    /// greedy, aircraft-at-a-time claiming: rather than letting each aircraft grab the best chain
+    /// for itself in turn (which can starve a later aircraft of flights it could have covered
+    /// more cheaply), this formulates `surplus_aircraft` x `unfulfilled` jointly as a resource
+    /// assignment problem — each surplus aircraft is a reusable resource, each unfulfilled flight
+    /// a request occupying it for `[max(sched_depart + dep_delay, next_available), depart +
+    /// est_duration + turnaround]` — and searches all ways to assign non-conflicting chains to
+    /// aircraft, maximizing flights covered with total delay as a secondary penalty.
+    struct OptimalAircraftSelectionStrategy {
+        surplus_aircraft: Vec<(DateTime<Utc>, String, AirportCode)>,
+        unfulfilled: HashMap<AirportCode, Vec<FlightId>>,
+        cached_reservations: Option<HashMap<FlightId, String>>,
+        last_ran: Option<DateTime<Utc>>,
+        max_depth: u32,
+        objective: ObjectiveKind,
+    }
+
+    impl OptimalAircraftSelectionStrategy {
+        fn new(max_depth: u32, objective: ObjectiveKind) -> Self {
+            Self {
+                surplus_aircraft: Vec::new(),
+                unfulfilled: HashMap::new(),
+                cached_reservations: None,
+                last_ran: None,
+                max_depth,
+                objective,
+            }
+        }
+
+        /// Every time-and-location-feasible chain (not just maximal ones — a shorter chain can
+        /// be the joint optimum if it frees up a flight a different aircraft needs) reachable
+        /// from `(start, origin)` within `max_depth` legs, expanded by the same feasibility
+        /// window and turnaround accounting `DfsAircraftSelectionStrategy::run_dfs` uses.
+        fn candidate_chains(
+            &self,
+            model: &Model,
+            start: DateTime<Utc>,
+            origin: AirportCode,
+        ) -> Vec<Chain> {
+            let mut chains = Vec::new();
+            let mut frontier = vec![(Vec::<FlightId>::new(), origin, start, 0.0f64, 0.0f64, start, 0u32)];
+            while let Some((
+                trail,
+                location,
+                next_available,
+                delay_minutes,
+                weighted_delay_minutes,
+                latest_arrival,
+                depth,
+            )) = frontier.pop()
+            {
+                if depth >= self.max_depth {
+                    continue;
+                }
+                let Some(flights) = self.unfulfilled.get(&location) else {
+                    continue;
+                };
+                for flight_id in flights.iter().filter(|f| !trail.contains(*f)) {
+                    let Ok(flight) = model.flights[flight_id].try_read() else {
+                        continue;
+                    };
+                    let feasible = flight.sched_depart + flight.dep_delay - next_available
+                        > TimeDelta::hours(-2)
+                        && next_available - flight.sched_depart < model.config.max_delay;
+                    if !feasible {
+                        continue;
+                    }
+                    let depart_time =
+                        std::cmp::max(flight.sched_depart + flight.dep_delay, next_available);
+                    let leg_delay_delta = depart_time - (flight.sched_depart + flight.dep_delay);
+                    let leg_delay = leg_delay_delta.num_seconds() as f64 / 60.0;
+                    let arrival_time = depart_time + flight.est_duration();
+                    let mut next_trail = trail.clone();
+                    next_trail.push(*flight_id);
+                    let next_weighted_delay_minutes = weighted_delay_minutes
+                        + leg_delay_delta.num_minutes() as f64 * passenger_load(&flight);
+                    let next_latest_arrival = std::cmp::max(latest_arrival, arrival_time);
+                    let next_available = arrival_time + model.config.aircraft_turnaround_time;
+                    chains.push(Chain {
+                        trail: next_trail.clone(),
+                        delay_minutes: delay_minutes + leg_delay,
+                        weighted_delay_minutes: next_weighted_delay_minutes,
+                        latest_arrival: next_latest_arrival,
+                    });
+                    frontier.push((
+                        next_trail,
+                        flight.dest,
+                        next_available,
+                        delay_minutes + leg_delay,
+                        next_weighted_delay_minutes,
+                        next_latest_arrival,
+                        depth + 1,
+                    ));
+                }
+            }
+            chains
+        }
+
+        /// Admissible upper bound on the best score still achievable from aircraft `from..` on:
+        /// each remaining aircraft's best standalone chain, ignoring conflicts between them
+        /// (conflicts can only lower the true joint optimum, never raise it).
+        fn upper_bound(candidates: &[(String, Vec<Chain>)], from: usize, objective: ObjectiveKind) -> f64 {
+            candidates[from..]
+                .iter()
+                .map(|(_, chains)| chains.iter().map(|c| c.score(objective)).fold(0.0, f64::max))
+                .sum()
+        }
+
+        fn branch_and_bound(
+            candidates: &[(String, Vec<Chain>)],
+            idx: usize,
+            used: &mut HashSet<FlightId>,
+            current: &mut HashMap<FlightId, String>,
+            score: f64,
+            best_score: &mut f64,
+            best: &mut HashMap<FlightId, String>,
+            objective: ObjectiveKind,
+        ) {
+            if idx == candidates.len() {
+                if score > *best_score {
+                    *best_score = score;
+                    *best = current.clone();
+                }
+                return;
+            }
+            if score + Self::upper_bound(candidates, idx, objective) <= *best_score {
+                return; // Pruned: even the best case from here can't beat the incumbent.
+            }
+            // Branch: leave this aircraft unassigned.
+            Self::branch_and_bound(candidates, idx + 1, used, current, score, best_score, best, objective);
+            let (aircraft, chains) = &candidates[idx];
+            for chain in chains {
+                if chain.trail.is_empty() || chain.trail.iter().any(|f| used.contains(f)) {
+                    continue;
+                }
+                for flight in &chain.trail {
+                    used.insert(*flight);
+                    current.insert(*flight, aircraft.clone());
+                }
+                Self::branch_and_bound(
+                    candidates,
+                    idx + 1,
+                    used,
+                    current,
+                    score + chain.score(objective),
+                    best_score,
+                    best,
+                    objective,
+                );
+                for flight in &chain.trail {
+                    used.remove(flight);
+                    current.remove(flight);
+                }
+            }
+        }
+
+        fn run_search(&self, model: &Model) -> HashMap<FlightId, String> {
+            let candidates: Vec<(String, Vec<Chain>)> = self
+                .surplus_aircraft
+                .iter()
+                .map(|(start, aircraft, origin)| {
+                    (aircraft.clone(), self.candidate_chains(model, *start, *origin))
+                })
+                .collect();
+            let mut best = HashMap::new();
+            let mut best_score = 0.0;
+            Self::branch_and_bound(
+                &candidates,
+                0,
+                &mut HashSet::new(),
+                &mut HashMap::new(),
+                0.0,
+                &mut best_score,
+                &mut best,
+                self.objective,
+            );
+            best
+        }
+
+        fn remove_stale_flights(&mut self, model: &Model) {
+            for (_, v) in self.unfulfilled.iter_mut() {
+                v.retain(|f| {
+                    let flt = model.flight_read(*f);
+                    flt.sched_depart > model.now() - TimeDelta::hours(4) && !flt.cancelled
+                });
+            }
+        }
+    }
+
+    impl AircraftSelectionStrategy for OptimalAircraftSelectionStrategy {
+        fn select(&mut self, flight: FlightId, model: &Model) -> Option<String> {
+            if self.last_ran.is_none() || self.last_ran.unwrap() < model.now() - TimeDelta::minutes(15)
+            {
+                self.remove_stale_flights(model);
+                self.cached_reservations = Some(self.run_search(model));
+                self.last_ran = Some(model.now());
+            }
+            self.cached_reservations.as_ref().unwrap().get(&flight).cloned()
+        }
+
+        fn reassign_suggestions(&self, _model: &Model) -> HashMap<FlightId, String> {
+            self.cached_reservations.as_ref().unwrap_or(&HashMap::new()).clone()
+        }
+
+        fn on_flight_cancel(&mut self, flight: FlightId, model: &Model) {
+            let flight = model.flight_read(flight);
+            if let Some(tail) = &flight.aircraft_tail {
+                let acft = model.fleet[tail].read().unwrap();
+                if let Some(avail_time) = acft.available_time(model, &flight) {
+                    self.surplus_aircraft.retain(|i| i.1 != *tail);
+                    let index = match self.surplus_aircraft.binary_search_by_key(&avail_time, |i| i.0)
+                    {
+                        Ok(n) => n,
+                        Err(n) => n,
+                    };
+                    self.surplus_aircraft
+                        .insert(index, (avail_time, tail.clone(), flight.origin));
+                }
+                for (id, v) in &model.flights {
+                    if let Ok(next) = v.read() {
+                        if next.aircraft_tail.as_ref() == Some(tail)
+                            && next.sched_depart > flight.sched_depart
+                            && !next.cancelled
+                        {
+                            self.unfulfilled.entry(next.origin).or_insert_with(Vec::new).push(*id);
+                        }
+                    }
+                }
+            }
+        }
+
+        fn on_flight_depart(&mut self, flight: FlightId, model: &Model) {
+            let flight = model.flight_read(flight);
+            let acft = flight
+                .aircraft_tail
+                .as_ref()
+                .expect("Departed flight must have assigned aircraft!");
+            if let Some(index) = self.surplus_aircraft.iter().position(|(_, v, _)| *v == *acft) {
+                self.surplus_aircraft.remove(index);
+            }
+            self.unfulfilled.entry(flight.origin).and_modify(|vec| {
+                if let Some(i) = vec.iter().position(|id| *id == flight.id) {
+                    vec.remove(i);
+                }
+            });
+        }
+    }
+
+    /// Per-crew duty bookkeeping `ReserveCrewSelectionStrategy` uses to decide legality. This is
+    /// independent of `Crew::legal_for`'s flight-duty-period engine: duty only accumulates while
+    /// a crew member keeps getting reassigned, and only resets once `min_rest` has elapsed since
+    /// they were last freed, modeling duty regulations as a reserved time span rather than a
+    /// lookback.
+    struct CrewDutyState {
+        cumulative_duty: TimeDelta,
+        /// When this crew member was last freed (by a cancellation); cleared to `None` when they
+        /// next depart, and `None` while they're mid-duty on something.
+        last_freed_at: Option<DateTime<Utc>>,
+    }
+
+    /// Substantive `CrewSelectionStrategy`: maintains a pool of crew freed by cancellations per
+    /// base and, on a shortage, substitutes reserve crew who can legally cover the flight —
+    /// legal meaning both within `max_duty` of accumulated duty time and, once that's exceeded,
+    /// resting for at least `min_rest` since they were freed.
+    struct ReserveCrewSelectionStrategy {
+        available_by_base: HashMap<AirportCode, Vec<CrewId>>,
+        duty: HashMap<CrewId, CrewDutyState>,
+        max_duty: TimeDelta,
+        min_rest: TimeDelta,
+    }
+
+    impl ReserveCrewSelectionStrategy {
+        fn new(max_duty: TimeDelta, min_rest: TimeDelta) -> Self {
+            Self {
+                available_by_base: HashMap::new(),
+                duty: HashMap::new(),
+                max_duty,
+                min_rest,
+            }
+        }
+
+        /// Whether `id` can legally be assigned a flight of `flight_duration` starting `now`:
+        /// either their accumulated duty plus this leg stays under `max_duty`, or they've rested
+        /// at least `min_rest` since they were freed, which resets their duty clock.
+        fn is_legal(&self, id: CrewId, flight_duration: TimeDelta, now: DateTime<Utc>) -> bool {
+            let Some(state) = self.duty.get(&id) else {
+                return true; // Never tracked: assume fully rested.
+            };
+            match state.last_freed_at {
+                Some(freed_at) if now - freed_at >= self.min_rest => true,
+                _ => state.cumulative_duty + flight_duration <= self.max_duty,
+            }
+        }
+
+        fn free(&mut self, id: CrewId, base: AirportCode, now: DateTime<Utc>) {
+            self.available_by_base.entry(base).or_insert_with(Vec::new).push(id);
+            self.duty
+                .entry(id)
+                .or_insert(CrewDutyState { cumulative_duty: TimeDelta::zero(), last_freed_at: None })
+                .last_freed_at = Some(now);
+        }
+    }
+
+    impl CrewSelectionStrategy for ReserveCrewSelectionStrategy {
+        fn select(
+            &mut self,
+            flight: FlightId,
+            model: &Model,
+            unavailable_crew: Vec<CrewId>,
+        ) -> Option<Vec<CrewId>> {
+            let flt = model.flight_read(flight);
+            let now = model.now();
+            let flight_duration = flt.sched_arrive - flt.sched_depart;
+            let mut crew: Vec<CrewId> =
+                flt.crew.iter().copied().filter(|c| !unavailable_crew.contains(c)).collect();
+            let needed = unavailable_crew.len();
+            if needed == 0 {
+                return if crew.is_empty() { None } else { Some(crew) };
+            }
+
+            let mut substitutes = Vec::new();
+            if let Some(pool) = self.available_by_base.get(&flt.origin) {
+                for candidate in pool {
+                    if substitutes.len() == needed {
+                        break;
+                    }
+                    if crew.contains(candidate) || substitutes.contains(candidate) {
+                        continue;
+                    }
+                    if self.is_legal(*candidate, flight_duration, now) {
+                        substitutes.push(*candidate);
+                    }
+                }
+            }
+            if substitutes.len() < needed {
+                return None;
+            }
+
+            if let Some(pool) = self.available_by_base.get_mut(&flt.origin) {
+                pool.retain(|c| !substitutes.contains(c));
+            }
+            crew.extend(substitutes);
+            if crew.is_empty() {
+                None
+            } else {
+                Some(crew)
+            }
+        }
+
+        fn on_flight_cancel(&mut self, flight: FlightId, model: &Model) {
+            let flt = model.flight_read(flight);
+            let now = model.now();
+            for id in flt.crew.clone() {
+                self.free(id, flt.origin, now);
+            }
+        }
+
+        fn on_flight_depart(&mut self, flight: FlightId, model: &Model) {
+            let flt = model.flight_read(flight);
+            let now = model.now();
+            let duration = flt.sched_arrive - flt.sched_depart;
+            for id in &flt.crew {
+                if let Some(pool) = self.available_by_base.get_mut(&flt.origin) {
+                    pool.retain(|c| c != id);
+                }
+                let state = self
+                    .duty
+                    .entry(*id)
+                    .or_insert(CrewDutyState { cumulative_duty: TimeDelta::zero(), last_freed_at: None });
+                state.cumulative_duty = match state.last_freed_at.take() {
+                    // Rest satisfied the mandatory window since they were freed: clock reset.
+                    Some(freed_at) if now - freed_at >= self.min_rest => duration,
+                    _ => state.cumulative_duty + duration,
+                };
+            }
+        }
+    }
+
+    pub fn new_for_aircraft(
+        key: &str,
+        beam_width: u32,
+        max_depth: u32,
+        objective: &str,
+        max_ferry_legs: u32,
+        max_ferry_duration: TimeDelta,
+    ) -> Box<dyn AircraftSelectionStrategy> {
+        let objective = ObjectiveKind::parse(objective);
         match key {
             "giveup" => Box::new(GiveUpAircraftSelectionStrategy {}),
-            "dfs" => Box::new(DfsAircraftSelectionStrategy::new()),
+            "dfs" => Box::new(DfsAircraftSelectionStrategy::new(
+                objective,
+                max_ferry_legs,
+                max_ferry_duration,
+            )),
+            "astar" => {
+                Box::new(GraphSearchAircraftSelectionStrategy::new(u32::MAX, max_depth, objective))
+            }
+            "beam" => {
+                Box::new(GraphSearchAircraftSelectionStrategy::new(beam_width, max_depth, objective))
+            }
+            "optimal" => Box::new(OptimalAircraftSelectionStrategy::new(max_depth, objective)),
             _ => unimplemented!("aircraft selection strategy {:?}", key),
         }
     }
-    pub fn new_for_crew(key: &str) -> Box<dyn CrewSelectionStrategy> {
+    pub fn new_for_crew(
+        key: &str,
+        max_duty: TimeDelta,
+        min_rest: TimeDelta,
+    ) -> Box<dyn CrewSelectionStrategy> {
         match key {
             "giveup" => Box::new(GiveUpCrewSelectionStrategy {}),
+            "reserve" => Box::new(ReserveCrewSelectionStrategy::new(max_duty, min_rest)),
             _ => unimplemented!("crew selection strategy {:?}", key),
         }
     }
+
+    /// Sort key for `Dispatcher::batch_assign_aircraft`, picking which of several due,
+    /// aircraft-less flights gets first pick of the earliest-available compatible aircraft.
+    pub fn new_batch_assign_cost(key: &str) -> Box<dyn Fn(&Model, FlightId) -> f64> {
+        match key {
+            "sched_depart" => {
+                Box::new(|model, flight| model.flight_read(flight).sched_depart.timestamp() as f64)
+            }
+            _ => unimplemented!("batch assign cost {:?}", key),
+        }
+    }
 }
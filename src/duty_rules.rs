@@ -0,0 +1,279 @@
+//! Configurable flight-duty-period (FDP) and rest legality, replacing `Crew`'s former fixed
+//! "10 hours flown in the trailing 24" rule with something closer to a real FAR 117-style regime:
+//! max FDP depends on report time of day and how many segments are already in the duty period,
+//! minimum rest after a duty period scales with how long that duty ran, and a separate cap bounds
+//! cumulative flight time over a rolling multi-day window. All of it lives on `Far117LikeEngine`,
+//! a plain data struct hung off `ModelConfig::crew_duty_engine` so different scenarios can model
+//! different carriers' rulesets just by loading different numbers, the same way
+//! `aircraft_search_objective` parameterizes `strategies::new_for_aircraft` without a code change.
+
+use std::cmp::{max, min};
+
+use chrono::{DateTime, TimeDelta, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::aircraft::Flight;
+use crate::crew::Crew;
+use crate::model::Model;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Far117LikeEngine {
+    /// Max FDP, before any segment-count reduction, for a duty reporting inside
+    /// `[reduced_report_hour_start, reduced_report_hour_end)` UTC. Mirrors FAR 117 Table B's
+    /// early/late "unacclimated" report rows allowing a shorter FDP than a mid-day report.
+    pub fdp_reduced_report_hours: TimeDelta,
+    /// Max FDP, before any segment-count reduction, for a report outside that window.
+    pub fdp_base_report_hours: TimeDelta,
+    pub reduced_report_hour_start: u32,
+    pub reduced_report_hour_end: u32,
+    /// Knocked off the report-time max FDP for each segment beyond the first flown in the same
+    /// duty period, mirroring Table B's max FDP shrinking as scheduled segment count climbs.
+    pub fdp_reduction_per_segment: TimeDelta,
+    /// Max FDP is never reduced below this floor, regardless of segment count.
+    pub fdp_floor: TimeDelta,
+    /// Minimum rest required after a duty period is `max(min_rest_floor, duty_length *
+    /// min_rest_scale)`.
+    pub min_rest_floor: TimeDelta,
+    pub min_rest_scale: f64,
+    /// Cumulative flight time allowed within any trailing `cumulative_window`.
+    pub max_cumulative_flight_time: TimeDelta,
+    pub cumulative_window: TimeDelta,
+}
+
+impl Far117LikeEngine {
+    /// Minimum rest a crew member must take after a duty period of `duty_length` before a new
+    /// duty period (and its own FDP clock) begins.
+    pub fn min_rest_after(&self, duty_length: TimeDelta) -> TimeDelta {
+        let scaled = TimeDelta::seconds(
+            (duty_length.num_seconds() as f64 * self.min_rest_scale).round() as i64,
+        );
+        max(self.min_rest_floor, scaled)
+    }
+
+    /// Whether `crew` can legally add `flight` onto the end of their current duty period (or
+    /// start a fresh one, if their last duty period ended long enough ago), given both the FDP
+    /// limit for that period's report time/segment count and the rolling cumulative flight-time
+    /// cap.
+    pub fn legal_to_add(&self, crew: &Crew, flight: &Flight, model: &Model) -> bool {
+        let depart = flight.depart_time.unwrap_or(flight.sched_depart);
+        let arrive = flight.arrive_time.unwrap_or(flight.sched_arrive);
+
+        let periods = self.duty_periods(crew, model);
+        let (report_time, segment_count) = match periods.last() {
+            Some(&(report_time, duty_end, segments))
+                if depart - duty_end < self.min_rest_after(duty_end - report_time) =>
+            {
+                (report_time, segments + 1)
+            }
+            _ => (depart, 1),
+        };
+        if arrive - report_time > self.max_fdp(report_time, segment_count) {
+            return false;
+        }
+
+        let window_start = arrive - self.cumulative_window;
+        let cumulative =
+            self.flight_time_in_window(crew, model, window_start, arrive) + (arrive - depart);
+        cumulative <= self.max_cumulative_flight_time
+    }
+
+    fn max_fdp(&self, report_time: DateTime<Utc>, segment_count: u32) -> TimeDelta {
+        let base = if Self::in_report_window(
+            report_time.hour(),
+            self.reduced_report_hour_start,
+            self.reduced_report_hour_end,
+        ) {
+            self.fdp_reduced_report_hours
+        } else {
+            self.fdp_base_report_hours
+        };
+        let reduction = TimeDelta::seconds(
+            self.fdp_reduction_per_segment.num_seconds() * segment_count.saturating_sub(1) as i64,
+        );
+        max(self.fdp_floor, base - reduction)
+    }
+
+    fn in_report_window(hour: u32, start: u32, end: u32) -> bool {
+        if start <= end {
+            (start..end).contains(&hour)
+        } else {
+            hour >= start || hour < end
+        }
+    }
+
+    /// Groups `crew.duty` into duty periods, collapsing consecutive flights whose gap is shorter
+    /// than the rest owed for the duty accumulated so far. Returns `(report_time, duty_end,
+    /// segment_count)` per period, in order.
+    fn duty_periods(&self, crew: &Crew, model: &Model) -> Vec<(DateTime<Utc>, DateTime<Utc>, u32)> {
+        let mut periods: Vec<(DateTime<Utc>, DateTime<Utc>, u32)> = Vec::new();
+        for &flight_id in &crew.duty {
+            let flt = model.flight_read(flight_id);
+            let depart = flt.depart_time.unwrap_or(flt.sched_depart);
+            let arrive = flt.arrive_time.unwrap_or_else(|| flt.act_arrive_time());
+            match periods.last_mut() {
+                Some((report_time, duty_end, segments))
+                    if depart - *duty_end < self.min_rest_after(*duty_end - *report_time) =>
+                {
+                    *duty_end = arrive;
+                    *segments += 1;
+                }
+                _ => periods.push((depart, arrive, 1)),
+            }
+        }
+        periods
+    }
+
+    /// Total flight time `crew` has logged within `[start, end]`.
+    fn flight_time_in_window(
+        &self,
+        crew: &Crew,
+        model: &Model,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> TimeDelta {
+        crew.duty
+            .iter()
+            .map(|&flight_id| {
+                let flt = model.flight_read(flight_id);
+                let depart = flt.depart_time.unwrap_or(flt.sched_depart);
+                let arrive = flt.arrive_time.unwrap_or_else(|| flt.act_arrive_time());
+                let lo = max(depart, start);
+                let hi = min(arrive, end);
+                if hi > lo {
+                    hi - lo
+                } else {
+                    TimeDelta::zero()
+                }
+            })
+            .fold(TimeDelta::zero(), |a, b| a + b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aircraft::{Flight, FlightId, Location};
+    use crate::airport::{AirportCode, DisruptionIndex};
+    use crate::crew::Crew;
+    use crate::model::ModelConfig;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn test_engine() -> Far117LikeEngine {
+        Far117LikeEngine {
+            fdp_reduced_report_hours: TimeDelta::hours(8),
+            fdp_base_report_hours: TimeDelta::hours(9),
+            reduced_report_hour_start: 2,
+            reduced_report_hour_end: 5,
+            fdp_reduction_per_segment: TimeDelta::minutes(30),
+            fdp_floor: TimeDelta::hours(8),
+            min_rest_floor: TimeDelta::hours(10),
+            min_rest_scale: 1.0,
+            max_cumulative_flight_time: TimeDelta::hours(100),
+            cumulative_window: TimeDelta::days(28),
+        }
+    }
+
+    #[test]
+    fn max_fdp_reduces_per_segment_down_to_the_floor() {
+        let engine = test_engine();
+        // Hour 12 falls outside the reduced-report window [2, 5), so the base (non-reduced) FDP
+        // applies before any segment reduction.
+        let report_time = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(engine.max_fdp(report_time, 1), TimeDelta::hours(9));
+        assert_eq!(engine.max_fdp(report_time, 2), TimeDelta::minutes(8 * 60 + 30));
+        // Enough segments to drive the per-segment reduction past zero; max_fdp must clamp at
+        // fdp_floor rather than go negative.
+        assert_eq!(engine.max_fdp(report_time, 20), engine.fdp_floor);
+    }
+
+    #[test]
+    fn in_report_window_wraps_past_midnight() {
+        // start > end models a window that wraps around midnight, e.g. [22, 5).
+        assert!(Far117LikeEngine::in_report_window(23, 22, 5));
+        assert!(Far117LikeEngine::in_report_window(0, 22, 5));
+        assert!(!Far117LikeEngine::in_report_window(12, 22, 5));
+    }
+
+    fn test_model(now: DateTime<Utc>, flights: Vec<Flight>, engine: Far117LikeEngine) -> Model {
+        let (publisher, _) = std::sync::mpsc::channel();
+        let (event_broadcast, _) = tokio::sync::broadcast::channel(1);
+        Model {
+            _now: std::sync::Arc::new(std::sync::RwLock::new(now)),
+            end: now + TimeDelta::hours(24),
+            fleet: HashMap::new(),
+            crew: HashMap::new(),
+            airports: HashMap::new(),
+            flights: flights
+                .into_iter()
+                .map(|f| (f.id, std::sync::Arc::new(std::sync::RwLock::new(f))))
+                .collect(),
+            disruptions: DisruptionIndex::new(),
+            publisher,
+            event_broadcast,
+            metrics: std::sync::RwLock::new(None),
+            config: ModelConfig {
+                crew_turnaround_time: TimeDelta::minutes(30),
+                aircraft_turnaround_time: TimeDelta::minutes(30),
+                max_delay: TimeDelta::hours(6),
+                aircraft_search_beam_width: u32::MAX,
+                aircraft_search_max_depth: 4,
+                crew_max_duty: TimeDelta::hours(10),
+                crew_min_rest: TimeDelta::hours(10),
+                aircraft_search_objective: "coverage".to_string(),
+                aircraft_max_ferry_legs: 2,
+                aircraft_max_ferry_duration: TimeDelta::hours(6),
+                assignment_window_violation_weight: 2.0,
+                assignment_deadhead_penalty: 30.0,
+                crew_duty_engine: engine,
+            },
+        }
+    }
+
+    fn test_flight(id: FlightId, sched_depart: DateTime<Utc>, duration: TimeDelta) -> Flight {
+        Flight {
+            id,
+            flight_number: format!("F{}", id),
+            aircraft_tail: Some("N1".to_string()),
+            crew: vec![1],
+            passengers: Vec::new(),
+            origin: AirportCode::from(&"AAA".to_owned()),
+            dest: AirportCode::from(&"BBB".to_owned()),
+            cancelled: false,
+            depart_time: Some(sched_depart),
+            arrive_time: Some(sched_depart + duration),
+            dep_delay: TimeDelta::zero(),
+            accum_delay: None,
+            sched_depart,
+            sched_arrive: sched_depart + duration,
+        }
+    }
+
+    #[test]
+    fn legal_to_add_rejects_once_cumulative_flight_time_cap_would_be_exceeded() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 10, 12, 0, 0).unwrap();
+        let mut engine = test_engine();
+        engine.max_cumulative_flight_time = TimeDelta::hours(5);
+
+        // A full day of rest before this flight is well past min_rest_after, so it starts its own
+        // duty period rather than merging with the one below — this test is only exercising the
+        // cumulative-flight-time cap, not the FDP/segment-count check.
+        let existing = test_flight(1, now - TimeDelta::days(1), TimeDelta::hours(4));
+        let model = test_model(now, vec![existing], engine.clone());
+        let crew = Crew {
+            id: 1,
+            location: Location::Ground(AirportCode::from(&"AAA".to_owned()), now - TimeDelta::hours(2)),
+            duty: vec![1],
+            next_claimed: None,
+        };
+
+        // 4h already flown within the cumulative window; adding a 2h flight would push the total
+        // to 6h, over the 5h cap.
+        let over_cap = test_flight(2, now, TimeDelta::hours(2));
+        assert!(!engine.legal_to_add(&crew, &over_cap, &model));
+
+        // A shorter flight that keeps the total at 4.5h, under the cap, is still legal.
+        let under_cap = test_flight(3, now, TimeDelta::minutes(30));
+        assert!(engine.legal_to_add(&crew, &under_cap, &model));
+    }
+}
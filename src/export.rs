@@ -2,15 +2,26 @@ use std::{error::Error, fs::File, sync::Arc};
 
 use chrono::{DateTime, Utc};
 use csv::Writer;
+use serde::Serialize;
 
-use crate::model::Model;
+use crate::{
+    aircraft::FlightId,
+    airport::{AirportCode, PassengerDemand},
+    journey_metrics::{journey_delay_minutes, misconnected},
+    metrics::{CancelReason, DelayReason, MetricsProcessor},
+    model::Model,
+};
 
 pub fn export_finished_model(
     model: Arc<Model>,
+    metrics: &MetricsProcessor,
     filename_prefix: &str,
 ) -> Result<(), Box<dyn Error>> {
     let mut flight_writer = Writer::from_path(format!("{}-flights.csv", filename_prefix))?;
     export_flights(&model, &mut flight_writer)?;
+    let mut passenger_writer = Writer::from_path(format!("{}-passengers.csv", filename_prefix))?;
+    export_passengers(&model, &mut passenger_writer)?;
+    export_report(&model, metrics, filename_prefix)?;
     Ok(())
 }
 
@@ -66,3 +77,243 @@ fn export_flights(model: &Model, writer: &mut Writer<File>) -> Result<(), Box<dy
 fn format_datetime(dt: &DateTime<Utc>) -> String {
     format!("{}", dt.format("%Y-%m-%d %H:%M:%S"))
 }
+
+/// Every passenger group still tracked by the model, one row each: groups that completed
+/// their itinerary (`Delivered`/`Misconnected`, found on the arrived flight that delivered
+/// them), groups still airborne (`InFlight`), and groups still waiting at an airport for their
+/// next leg, including ones that never got further than their origin (`Stranded`).
+fn export_passengers(model: &Model, writer: &mut Writer<File>) -> Result<(), Box<dyn Error>> {
+    writer.write_record(&[
+        "origin",
+        "dest",
+        "path",
+        "count",
+        "flights_taken",
+        "status",
+        "delay_minutes",
+    ])?;
+
+    let mut flight_ids: Vec<&FlightId> = model.flights.keys().collect();
+    flight_ids.sort();
+    for id in flight_ids {
+        let flt = model.flights[id].read().unwrap();
+        if flt.cancelled {
+            continue;
+        }
+        for demand in &flt.passengers {
+            if flt.arrive_time.is_none() {
+                write_passenger_row(writer, demand, "InFlight", None)?;
+            } else if demand.path.last() == Some(&flt.dest) {
+                let delay = journey_delay_minutes(model, &demand.flights_taken);
+                let status = if misconnected(model, &demand.flights_taken) {
+                    "Misconnected"
+                } else {
+                    "Delivered"
+                };
+                write_passenger_row(writer, demand, status, Some(delay))?;
+            }
+            // else: this leg is done but the group isn't at its final destination yet; it has
+            // already been re-queued onto the connecting airport and is exported from there.
+        }
+    }
+
+    let mut airport_codes: Vec<&AirportCode> = model.airports.keys().collect();
+    airport_codes.sort_by_key(|code| code.to_string());
+    for code in airport_codes {
+        let airport = model.airports[code].read().unwrap();
+        for demand in &airport.passengers {
+            write_passenger_row(writer, demand, "Stranded", None)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_passenger_row(
+    writer: &mut Writer<File>,
+    demand: &PassengerDemand,
+    status: &str,
+    delay_minutes: Option<i64>,
+) -> Result<(), Box<dyn Error>> {
+    writer.write_record(&[
+        &format!("{}", demand.path.first().unwrap()),
+        &format!("{}", demand.path.last().unwrap()),
+        &demand
+            .path
+            .iter()
+            .map(|code| code.to_string())
+            .collect::<Vec<String>>()
+            .join("->"),
+        &demand.count.to_string(),
+        &demand
+            .flights_taken
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(","),
+        status,
+        &delay_minutes.map(|d| d.to_string()).unwrap_or_default(),
+    ])?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DisruptionReport {
+    metadata: ReportMetadata,
+    departure_delay_causes: Vec<DelayCauseRecord>,
+    arrival_delay_causes: Vec<DelayCauseRecord>,
+    rate_limited_airports: Vec<AirportImpactRecord>,
+    cancellations: Vec<CancellationRecord>,
+    otp: Vec<OtpRecord>,
+}
+
+#[derive(Serialize)]
+struct ReportMetadata {
+    /// Earliest scheduled departure across all flights, as a proxy for when the scenario's
+    /// traffic starts (the model doesn't retain its own original clock value once run).
+    sim_start: Option<String>,
+    sim_end: String,
+    max_delay_minutes: i64,
+    crew_turnaround_minutes: i64,
+    aircraft_turnaround_minutes: i64,
+}
+
+#[derive(Serialize)]
+struct DelayCauseRecord {
+    reason: String,
+    total_minutes: u32,
+    flight_count: u32,
+}
+
+#[derive(Serialize)]
+struct AirportImpactRecord {
+    airport: String,
+    total_minutes: u32,
+    flight_count: u32,
+}
+
+#[derive(Serialize)]
+struct CancellationRecord {
+    reason: String,
+    count: u32,
+}
+
+#[derive(Serialize)]
+struct OtpRecord {
+    time: String,
+    on_time: u32,
+    total: u32,
+    cancelled: u32,
+}
+
+/// Write a structured, machine-readable summary of the run to `{prefix}-report.json`: delay
+/// causes (split departure vs arrival), per-airport rate-limiting impact, the cancellation
+/// breakdown, and the final OTP curve. Meant for diffing across recovery policies rather than
+/// for a human to read directly.
+fn export_report(
+    model: &Model,
+    metrics: &MetricsProcessor,
+    filename_prefix: &str,
+) -> Result<(), Box<dyn Error>> {
+    let sim_start = model
+        .flights
+        .values()
+        .map(|flt| flt.read().unwrap().sched_depart)
+        .min();
+
+    let metadata = ReportMetadata {
+        sim_start: sim_start.as_ref().map(format_datetime),
+        sim_end: format_datetime(&model.end),
+        max_delay_minutes: model.config.max_delay.num_minutes(),
+        crew_turnaround_minutes: model.config.crew_turnaround_time.num_minutes(),
+        aircraft_turnaround_minutes: model.config.aircraft_turnaround_time.num_minutes(),
+    };
+
+    let departure_delay_causes = delay_cause_records(&metrics.dep_delay_causes, &metrics.dep_delay_counts);
+    let arrival_delay_causes = delay_cause_records(&metrics.arr_delay_causes, &metrics.arr_delay_counts);
+    let rate_limited_airports = rate_limited_airport_records(
+        &metrics.dep_delay_causes,
+        &metrics.dep_delay_counts,
+        &metrics.arr_delay_causes,
+        &metrics.arr_delay_counts,
+    );
+    let cancellations = metrics
+        .cancellations
+        .iter()
+        .map(|(reason, count)| CancellationRecord {
+            reason: cancel_reason_label(reason),
+            count: *count,
+        })
+        .collect();
+    let otp = metrics
+        .otp
+        .iter()
+        .map(|(time, (on_time, total, cancelled))| OtpRecord {
+            time: format_datetime(time),
+            on_time: *on_time,
+            total: *total,
+            cancelled: *cancelled,
+        })
+        .collect();
+
+    let report = DisruptionReport {
+        metadata,
+        departure_delay_causes,
+        arrival_delay_causes,
+        rate_limited_airports,
+        cancellations,
+        otp,
+    };
+
+    let file = File::create(format!("{}-report.json", filename_prefix))?;
+    serde_json::to_writer_pretty(file, &report)?;
+    Ok(())
+}
+
+fn delay_cause_records(
+    minutes: &std::collections::HashMap<DelayReason, u32>,
+    counts: &std::collections::HashMap<DelayReason, u32>,
+) -> Vec<DelayCauseRecord> {
+    minutes
+        .iter()
+        .map(|(reason, total_minutes)| DelayCauseRecord {
+            reason: format!("{:?}", reason),
+            total_minutes: *total_minutes,
+            flight_count: counts.get(reason).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+fn rate_limited_airport_records(
+    dep_minutes: &std::collections::HashMap<DelayReason, u32>,
+    dep_counts: &std::collections::HashMap<DelayReason, u32>,
+    arr_minutes: &std::collections::HashMap<DelayReason, u32>,
+    arr_counts: &std::collections::HashMap<DelayReason, u32>,
+) -> Vec<AirportImpactRecord> {
+    let mut by_airport: std::collections::HashMap<AirportCode, (u32, u32)> = std::collections::HashMap::new();
+    for (minutes, counts) in [(dep_minutes, dep_counts), (arr_minutes, arr_counts)] {
+        for (reason, total_minutes) in minutes {
+            if let DelayReason::RateLimited(airport) = reason {
+                let entry = by_airport.entry(*airport).or_insert((0, 0));
+                entry.0 += total_minutes;
+                entry.1 += counts.get(reason).copied().unwrap_or(0);
+            }
+        }
+    }
+    by_airport
+        .into_iter()
+        .map(|(airport, (total_minutes, flight_count))| AirportImpactRecord {
+            airport: airport.to_string(),
+            total_minutes,
+            flight_count,
+        })
+        .collect()
+}
+
+fn cancel_reason_label(reason: &CancelReason) -> String {
+    match reason {
+        CancelReason::HeavyExpectedDelay(delay_reason) => {
+            format!("HeavyExpectedDelay({:?})", delay_reason)
+        }
+        CancelReason::DelayTimedOut => "DelayTimedOut".to_string(),
+    }
+}
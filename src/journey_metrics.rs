@@ -0,0 +1,40 @@
+//! Per-passenger-journey delay/misconnection accounting shared by `metrics.rs` (live run stats)
+//! and `export.rs` (the final per-passenger CSV), so the two don't drift out of step.
+
+use std::cmp::max;
+
+use chrono::TimeDelta;
+
+use crate::{aircraft::FlightId, model::Model};
+
+/// A gap of up to this many minutes between one leg's arrival and the next leg's departure is a
+/// connection made; anything over this gap counts as a missed connection.
+pub const MISCONNECT_THRESHOLD_MINUTES: i64 = 60;
+
+/// Total arrival delay, in minutes, accumulated across every leg of a passenger group's journey
+/// so far.
+pub fn journey_delay_minutes(model: &Model, flights_taken: &[FlightId]) -> i64 {
+    flights_taken
+        .iter()
+        .map(|id| {
+            let flt = model.flight_read(*id);
+            let delay = flt
+                .arrive_time
+                .map(|t| t - flt.sched_arrive)
+                .unwrap_or_else(TimeDelta::zero);
+            max(TimeDelta::zero(), delay).num_minutes()
+        })
+        .sum()
+}
+
+/// Whether any leg-to-leg gap in the journey so far exceeded `MISCONNECT_THRESHOLD_MINUTES`.
+pub fn misconnected(model: &Model, flights_taken: &[FlightId]) -> bool {
+    flights_taken.windows(2).any(|pair| {
+        let prev = model.flight_read(pair[0]);
+        let next = model.flight_read(pair[1]);
+        matches!(
+            (prev.arrive_time, next.depart_time),
+            (Some(arrive), Some(depart)) if (depart - arrive).num_minutes() > MISCONNECT_THRESHOLD_MINUTES
+        )
+    })
+}
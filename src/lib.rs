@@ -1,18 +1,35 @@
 extern crate chrono;
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
+use aircraft::{Flight, FlightId};
+use airport::{AirportCode, PassengerDemand};
 use metrics::MetricsProcessor;
 use model::Model;
 use neon::prelude::*;
-use scenario::{ScenarioLoader, SqliteScenarioLoader};
+use scenario::{ScenarioLoader, ScenarioSource, SqliteScenarioLoader};
 
+mod adsb;
 mod aircraft;
 mod airport;
+mod assignment_cost;
+mod conflict_graph;
 mod crew;
 mod dispatcher;
+mod duty_rules;
+mod grpc;
+mod journey_metrics;
+mod live_feed;
 mod metrics;
 mod model;
+mod objective;
 mod scenario;
+mod slot_clock;
+mod slot_solver;
+mod snapshot;
+mod timer;
 mod export;
 
 macro_rules! try_load {
@@ -43,60 +60,75 @@ struct FinishedModel {
 
 impl Finalize for FinishedModel {}
 
+/// Build the JS object `encode_model`/`next_export_chunk` both represent a single `Flight` as.
+fn encode_flight<'a>(cx: &mut FunctionContext<'a>, flight: &Flight) -> JsResult<'a, JsObject> {
+    let obj = cx.empty_object();
+    if !flight.cancelled {
+        if let Some(depart_time) = flight.depart_time {
+            let val = cx.number(depart_time.timestamp() as f64 * 1000f64);
+            obj.set(cx, "start", val)?;
+        }
+        if let Some(arrive_time) = flight.arrive_time {
+            let val = cx.number(arrive_time.timestamp() as f64 * 1000f64);
+            obj.set(cx, "end", val)?;
+        }
+    }
+    let sched_start = cx.number(flight.sched_depart.timestamp() as f64 * 1000f64);
+    obj.set(cx, "sched_start", sched_start)?;
+    let sched_end = cx.number(flight.sched_arrive.timestamp() as f64 * 1000f64);
+    obj.set(cx, "sched_end", sched_end)?;
+    let origin = cx.string(flight.origin.to_string());
+    obj.set(cx, "origin", origin)?;
+    let dest = cx.string(flight.dest.to_string());
+    obj.set(cx, "dest", dest)?;
+    let flight_number = cx.string(flight.flight_number.to_string());
+    obj.set(cx, "flight_number", flight_number)?;
+    if let Some(tail) = flight.aircraft_tail.clone() {
+        let tail_val = cx.string(tail);
+        obj.set(cx, "tail", tail_val)?;
+    } else {
+        let null_val = cx.null();
+        obj.set(cx, "tail", null_val)?;
+    }
+    let cancelled = cx.boolean(flight.cancelled);
+    obj.set(cx, "cancelled", cancelled)?;
+    Ok(obj)
+}
+
+/// Build the JS array `encode_model`/`next_export_chunk` both represent one airport's
+/// `PassengerDemand` backlog as.
+fn encode_demands<'a>(
+    cx: &mut FunctionContext<'a>,
+    demands: &[PassengerDemand],
+) -> JsResult<'a, JsArray> {
+    let value = cx.empty_array();
+    for (i, demand) in demands.iter().enumerate() {
+        let obj = cx.empty_object();
+        let path = cx.empty_array();
+        for (j, code) in demand.path.iter().enumerate() {
+            let code_val = cx.string(code.to_string());
+            path.set(cx, j as u32, code_val)?;
+        }
+        obj.set(cx, "path", path)?;
+        let count = cx.number(demand.count);
+        obj.set(cx, "count", count)?;
+        let flights = cx.empty_array();
+        for (j, flight) in demand.flights_taken.iter().enumerate() {
+            let flight_val = cx.number(*flight as u32);
+            flights.set(cx, j as u32, flight_val)?;
+        }
+        obj.set(cx, "flights", flights)?;
+        value.set(cx, i as u32, obj)?;
+    }
+    Ok(value)
+}
+
 fn encode_model(mut cx: FunctionContext) -> JsResult<JsObject> {
     let finished_model = &cx.argument::<JsBox<FinishedModel>>(0)?;
     let model = &finished_model.model;
     let flights = cx.empty_object();
     for (flight_id, flight) in &model.flights {
-        let flight = {
-            let flight = flight.read().unwrap();
-            let obj = cx.empty_object();
-            if !flight.cancelled {
-                if let Some(depart_time) = flight.depart_time {
-                    object_set!(
-                        cx,
-                        obj,
-                        "start",
-                        cx.number(depart_time.timestamp() as f64 * 1000f64)
-                    );
-                }
-                if let Some(arrive_time) = flight.arrive_time {
-                    object_set!(
-                        cx,
-                        obj,
-                        "end",
-                        cx.number(arrive_time.timestamp() as f64 * 1000f64)
-                    );
-                }
-            }
-            object_set!(
-                cx,
-                obj,
-                "sched_start",
-                cx.number(flight.sched_depart.timestamp() as f64 * 1000f64)
-            );
-            object_set!(
-                cx,
-                obj,
-                "sched_end",
-                cx.number(flight.sched_arrive.timestamp() as f64 * 1000f64)
-            );
-            object_set!(cx, obj, "origin", cx.string(flight.origin.to_string()));
-            object_set!(cx, obj, "dest", cx.string(flight.dest.to_string()));
-            object_set!(
-                cx,
-                obj,
-                "flight_number",
-                cx.string(flight.flight_number.to_string())
-            );
-            if let Some(tail) = flight.aircraft_tail.clone() {
-                object_set!(cx, obj, "tail", cx.string(tail));
-            } else {
-                object_set!(cx, obj, "tail", cx.null());
-            }
-            object_set!(cx, obj, "cancelled", cx.boolean(flight.cancelled));
-            Ok(obj)
-        }?;
+        let flight = encode_flight(&mut cx, &flight.read().unwrap())?;
         object_set!(cx, flights, flight_id.to_string().as_str(), flight);
     }
     let fleet = cx.empty_object();
@@ -106,32 +138,29 @@ fn encode_model(mut cx: FunctionContext) -> JsResult<JsObject> {
     }
     let demands = cx.empty_object();
     for (loc, airport) in &model.airports {
-        let airport = airport.read().unwrap();
-        let value = cx.empty_array();
-        for (i, demand) in airport.passengers.iter().enumerate() {
-            let obj = cx.empty_object();
-            let path = cx.empty_array();
-            for (i, code) in demand.path.iter().enumerate() {
-                object_set!(cx, path, i as u32, cx.string(code.to_string()));
-            }
-            object_set!(cx, obj, "path", path);
-            object_set!(cx, obj, "count", cx.number(demand.count));
-            let flights = cx.empty_array();
-            for (i, flight) in demand.flights_taken.iter().enumerate() {
-                object_set!(cx, flights, i as u32, cx.number(*flight as u32));
-            }
-            object_set!(cx, obj, "flights", flights);
-            object_set!(cx, value, i as u32, obj);
-        }
+        let value = encode_demands(&mut cx, &airport.read().unwrap().passengers)?;
         object_set!(cx, demands, loc.to_string().as_str(), value);
     }
     let metrics = {
         let arrival_delay_dist = {
-            let arr = cx.empty_array();
-            for (i, delay) in finished_model.metrics.arrival_delays.iter().enumerate() {
-                object_set!(cx, arr, i as u32, cx.number(*delay as i32));
-            }
-            arr
+            let stats = finished_model.metrics.arrival_delay_stats();
+            let obj = cx.empty_object();
+            object_set!(cx, obj, "p50", cx.number(stats.p50 as f64));
+            object_set!(cx, obj, "p90", cx.number(stats.p90 as f64));
+            object_set!(cx, obj, "p99", cx.number(stats.p99 as f64));
+            object_set!(cx, obj, "mean", cx.number(stats.mean));
+            object_set!(cx, obj, "max", cx.number(stats.max as f64));
+            obj
+        };
+        let departure_delay_dist = {
+            let stats = finished_model.metrics.departure_delay_stats();
+            let obj = cx.empty_object();
+            object_set!(cx, obj, "p50", cx.number(stats.p50 as f64));
+            object_set!(cx, obj, "p90", cx.number(stats.p90 as f64));
+            object_set!(cx, obj, "p99", cx.number(stats.p99 as f64));
+            object_set!(cx, obj, "mean", cx.number(stats.mean));
+            object_set!(cx, obj, "max", cx.number(stats.max as f64));
+            obj
         };
         let otp = {
             let obj = cx.empty_object();
@@ -155,6 +184,7 @@ fn encode_model(mut cx: FunctionContext) -> JsResult<JsObject> {
         
         let obj = cx.empty_object();
         obj.set(&mut cx, "delays", arrival_delay_dist)?;
+        obj.set(&mut cx, "dep_delays", departure_delay_dist)?;
         obj.set(&mut cx, "otp", otp)?;
         obj.set(&mut cx, "dep_delay_reasons", dep_delay_reasons)?;
         obj.set(&mut cx, "arr_delay_reasons", arr_delay_reasons)?;
@@ -169,14 +199,76 @@ fn encode_model(mut cx: FunctionContext) -> JsResult<JsObject> {
     Ok(obj)
 }
 
+/// Loads `scenario` from the sqlite database at `path` and runs it to completion. Shared by
+/// `run_model` (blocking) and `run_model_async` (off the JS thread via `cx.task`), so the two
+/// entry points can't drift apart on how a scenario actually gets run.
+fn run_model_blocking(path: &str, scenario: String) -> Result<FinishedModel, String> {
+    let loader = SqliteScenarioLoader::new(path, scenario)
+        .map_err(|error| format!("Failed to load scenario: {:?}", error))?;
+    let model = Arc::new(
+        loader
+            .read_model()
+            .map_err(|error| format!("Failed to load scenario: {:?}", error))?,
+    );
+    let mut dispatcher = loader
+        .read_dispatcher(model.clone())
+        .map_err(|error| format!("Failed to load scenario: {:?}", error))?;
+
+    dispatcher.init_flight_updates();
+    dispatcher.run_model();
+    let Some(handle) = model.metrics.write().unwrap().take() else { panic!() };
+    let metrics = handle.join().expect("Metrics thread failed");
+
+    Ok(FinishedModel { model, metrics })
+}
+
 fn run_model(mut cx: FunctionContext) -> JsResult<JsBox<FinishedModel>> {
     let path = cx.argument::<JsString>(0)?.value(&mut cx);
     let scenario = cx.argument::<JsString>(1)?.value(&mut cx);
+    match run_model_blocking(&path, scenario) {
+        Ok(finished) => Ok(cx.boxed(finished)),
+        Err(message) => cx.throw_error(message),
+    }
+}
+
+/// Like `run_model`, but runs the scenario on a background task-pool thread and resolves a JS
+/// promise instead of blocking the JS thread for the whole run.
+fn run_model_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let scenario = cx.argument::<JsString>(1)?.value(&mut cx);
+
+    let promise = cx
+        .task(move || run_model_blocking(&path, scenario))
+        .promise(move |mut cx, result| match result {
+            Ok(finished) => Ok(cx.boxed(finished)),
+            Err(message) => cx.throw_error(message),
+        });
+
+    Ok(promise)
+}
+
+/// Like `run_model`, but first replays an ADS-B position feed against the scenario so already-
+/// observed legs are realized from their actual timestamps; the dispatcher only simulates
+/// whatever the feed never saw.
+fn run_live_model(mut cx: FunctionContext) -> JsResult<JsBox<FinishedModel>> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let scenario = cx.argument::<JsString>(1)?.value(&mut cx);
+    let feed = cx.argument::<JsString>(2)?.value(&mut cx);
+    let tail_registry = cx.argument::<JsString>(3)?.value(&mut cx);
     let loader = try_load!(&mut cx, SqliteScenarioLoader::new(&path, scenario));
     let model = Arc::new(try_load!(&mut cx, loader.read_model()));
     let mut dispatcher = try_load!(&mut cx, loader.read_dispatcher(model.clone()));
 
-    dispatcher.init_flight_updates();
+    let registry: adsb::TailRegistry = match serde_json::from_str(&tail_registry) {
+        Ok(registry) => registry,
+        Err(err) => return cx.throw_error(format!("Failed to parse tail registry: {}", err)),
+    };
+    let mut driver = adsb::AdsbFeedDriver::new(registry);
+    if let Err(err) = driver.ingest_str(&model, &feed) {
+        return cx.throw_error(format!("Failed to ingest ADS-B feed: {}", err));
+    }
+
+    dispatcher.init_flight_updates_from_observed();
     dispatcher.run_model();
     let Some(handle) = model.metrics.write().unwrap().take() else { panic!() };
     let metrics = handle.join().expect("Metrics thread failed");
@@ -184,21 +276,222 @@ fn run_model(mut cx: FunctionContext) -> JsResult<JsBox<FinishedModel>> {
     Ok(cx.boxed(FinishedModel { model, metrics }))
 }
 
+/// Like `run_model`, but first applies a live flight-status feed (delays, cancellations, observed
+/// actual times) as corrections to the freshly loaded scenario, so the dispatcher spends the rest
+/// of the run recovering from those exogenous updates instead of the frozen schedule alone. The
+/// feed is applied once up front rather than polled mid-run: `run_model`/`run_live_model` are both
+/// single blocking calls with no hook for a caller to inject further updates once the dispatcher
+/// loop has started.
+fn run_model_with_live_feed(mut cx: FunctionContext) -> JsResult<JsBox<FinishedModel>> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let scenario = cx.argument::<JsString>(1)?.value(&mut cx);
+    let feed = cx.argument::<JsString>(2)?.value(&mut cx);
+    let loader = try_load!(&mut cx, SqliteScenarioLoader::new(&path, scenario));
+    let model = Arc::new(try_load!(&mut cx, loader.read_model()));
+    let mut dispatcher = try_load!(&mut cx, loader.read_dispatcher(model.clone()));
+
+    if let Err(err) = live_feed::LiveFeedLoader::ingest_str(&model, &feed) {
+        return cx.throw_error(format!("Failed to ingest live status feed: {}", err));
+    }
+
+    dispatcher.init_flight_updates_from_observed();
+    dispatcher.run_model();
+    let Some(handle) = model.metrics.write().unwrap().take() else { panic!() };
+    let metrics = handle.join().expect("Metrics thread failed");
+
+    Ok(cx.boxed(FinishedModel { model, metrics }))
+}
+
+/// Paginates a finished model's flights, then its airports' passenger demand, a chunk at a time,
+/// so a caller can stream a large model out to JS instead of `encode_model` materializing the
+/// whole thing into one object up front. Position counters are atomics rather than plain fields
+/// because `JsBox` only ever hands out `&ModelExportCursor`, never `&mut`.
+struct ModelExportCursor {
+    model: Arc<Model>,
+    flight_ids: Vec<FlightId>,
+    airport_codes: Vec<AirportCode>,
+    flight_position: AtomicUsize,
+    airport_position: AtomicUsize,
+}
+
+impl Finalize for ModelExportCursor {}
+
+fn create_export_cursor(mut cx: FunctionContext) -> JsResult<JsBox<ModelExportCursor>> {
+    let finished_model = cx.argument::<JsBox<FinishedModel>>(0)?;
+    let model = finished_model.model.clone();
+
+    let mut flight_ids: Vec<FlightId> = model.flights.keys().copied().collect();
+    flight_ids.sort();
+    let mut airport_codes: Vec<AirportCode> = model.airports.keys().cloned().collect();
+    airport_codes.sort();
+
+    Ok(cx.boxed(ModelExportCursor {
+        model,
+        flight_ids,
+        airport_codes,
+        flight_position: AtomicUsize::new(0),
+        airport_position: AtomicUsize::new(0),
+    }))
+}
+
+/// Returns the next `chunk_size` flights, as `{flights, demands, done}`. Once the flights are
+/// exhausted (including on the call that returns the last of them), `demands` also holds the next
+/// `chunk_size` airports' demand backlogs. `done` is set once both are exhausted.
+fn next_export_chunk(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let cursor = cx.argument::<JsBox<ModelExportCursor>>(0)?;
+    let chunk_size = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+
+    let flights = cx.empty_object();
+    let demands = cx.empty_object();
+
+    let flight_start = cursor.flight_position.fetch_add(chunk_size, Ordering::SeqCst);
+    let flight_start = flight_start.min(cursor.flight_ids.len());
+    let flight_end = (flight_start + chunk_size).min(cursor.flight_ids.len());
+    for &flight_id in &cursor.flight_ids[flight_start..flight_end] {
+        let flt = cursor.model.flight_read(flight_id);
+        let flight = encode_flight(&mut cx, &flt)?;
+        flights.set(&mut cx, flight_id.to_string().as_str(), flight)?;
+    }
+
+    if flight_end >= cursor.flight_ids.len() {
+        let airport_start = cursor.airport_position.fetch_add(chunk_size, Ordering::SeqCst);
+        let airport_start = airport_start.min(cursor.airport_codes.len());
+        let airport_end = (airport_start + chunk_size).min(cursor.airport_codes.len());
+        for code in &cursor.airport_codes[airport_start..airport_end] {
+            let airport = cursor.model.airports[code].read().unwrap();
+            let value = encode_demands(&mut cx, &airport.passengers)?;
+            demands.set(&mut cx, code.to_string().as_str(), value)?;
+        }
+
+        let done = airport_end >= cursor.airport_codes.len();
+        let obj = cx.empty_object();
+        obj.set(&mut cx, "flights", flights)?;
+        obj.set(&mut cx, "demands", demands)?;
+        let done_val = cx.boolean(done);
+        obj.set(&mut cx, "done", done_val)?;
+        return Ok(obj);
+    }
+
+    let obj = cx.empty_object();
+    obj.set(&mut cx, "flights", flights)?;
+    obj.set(&mut cx, "demands", demands)?;
+    let done_val = cx.boolean(false);
+    obj.set(&mut cx, "done", done_val)?;
+    Ok(obj)
+}
+
 fn export_csvs(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let model = cx.argument::<JsBox<FinishedModel>>(0)?;
     let prefix = cx.argument::<JsString>(1)?.value(&mut cx);
 
 
-    if let Err(err) = export::export_finished_model(model.model.clone(), &prefix) {
+    if let Err(err) = export::export_finished_model(model.model.clone(), &model.metrics, &prefix) {
         return cx.throw_error(err.to_string());
     }
     Ok(cx.undefined())
 }
 
+/// Handle to a live `grpc::RecovairEventService` started by `start_event_server`. The shutdown
+/// sender lives behind a mutex (rather than being consumed outright) only because `JsBox` never
+/// hands out `&mut Self`; `stop_event_server` takes it out on the one call that needs it.
+struct EventServerHandle {
+    shutdown: std::sync::Mutex<Option<tokio::sync::oneshot::Sender<()>>>,
+}
+
+impl Finalize for EventServerHandle {}
+
+/// So a dashboard or controller process can watch a run's `ModelEvent`s live: binds
+/// `grpc::RecovairEventService` to `127.0.0.1:<port>` on a background thread and returns a handle
+/// `stop_event_server` can later shut it down with.
+fn start_event_server(mut cx: FunctionContext) -> JsResult<JsBox<EventServerHandle>> {
+    let finished_model = cx.argument::<JsBox<FinishedModel>>(0)?;
+    let port = cx.argument::<JsNumber>(1)?.value(&mut cx) as u16;
+
+    let service = grpc::RecovairEventService {
+        broadcast: finished_model.model.event_broadcast.clone(),
+        model: Arc::downgrade(&finished_model.model),
+    };
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    let (_handle, shutdown) = grpc::spawn_event_server(service, addr);
+
+    Ok(cx.boxed(EventServerHandle {
+        shutdown: std::sync::Mutex::new(Some(shutdown)),
+    }))
+}
+
+fn stop_event_server(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let handle = cx.argument::<JsBox<EventServerHandle>>(0)?;
+    if let Some(shutdown) = handle.shutdown.lock().unwrap().take() {
+        let _ = shutdown.send(());
+    }
+    Ok(cx.undefined())
+}
+
+/// Writes `model`'s full state to `path` via `Model::snapshot`, so it can later be resumed with
+/// `restore_model` instead of replaying the scenario from scratch.
+fn snapshot_model(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let finished_model = cx.argument::<JsBox<FinishedModel>>(0)?;
+    let path = cx.argument::<JsString>(1)?.value(&mut cx);
+    if let Err(err) = finished_model.model.snapshot(&path) {
+        return cx.throw_error(err.to_string());
+    }
+    Ok(cx.undefined())
+}
+
+/// Resumes a model checkpointed by `snapshot_model` and runs it to completion. `Model::restore`
+/// only rebuilds the `Model` itself (see its doc comment), so a `Dispatcher` is still read from
+/// `scenario_db_path`/`scenario` the normal way and pointed at the restored model; flights already
+/// realized by the snapshot are picked up via `init_flight_updates_from_observed` rather than
+/// replayed from `sched_depart`.
+fn restore_model_blocking(
+    snapshot_path: &str,
+    scenario_db_path: &str,
+    scenario: String,
+) -> Result<FinishedModel, String> {
+    let loader = SqliteScenarioLoader::new(scenario_db_path, scenario)
+        .map_err(|error| format!("Failed to load scenario: {:?}", error))?;
+    let line_sink_path = loader
+        .config()
+        .map_err(|error| format!("Failed to load scenario: {:?}", error))?
+        .line_sink_path;
+    let model = Model::restore(snapshot_path, line_sink_path.as_deref())
+        .map_err(|error| format!("Failed to restore snapshot: {:?}", error))?;
+    let model = Arc::new(model);
+    let mut dispatcher = loader
+        .read_dispatcher(model.clone())
+        .map_err(|error| format!("Failed to load scenario: {:?}", error))?;
+
+    dispatcher.init_flight_updates_from_observed();
+    dispatcher.run_model();
+    let Some(handle) = model.metrics.write().unwrap().take() else { panic!() };
+    let metrics = handle.join().expect("Metrics thread failed");
+
+    Ok(FinishedModel { model, metrics })
+}
+
+fn restore_model(mut cx: FunctionContext) -> JsResult<JsBox<FinishedModel>> {
+    let snapshot_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let scenario_db_path = cx.argument::<JsString>(1)?.value(&mut cx);
+    let scenario = cx.argument::<JsString>(2)?.value(&mut cx);
+    match restore_model_blocking(&snapshot_path, &scenario_db_path, scenario) {
+        Ok(finished) => Ok(cx.boxed(finished)),
+        Err(message) => cx.throw_error(message),
+    }
+}
+
 #[neon::main]
 fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("runModel", run_model)?;
+    cx.export_function("runModelAsync", run_model_async)?;
+    cx.export_function("runLiveModel", run_live_model)?;
+    cx.export_function("runModelWithLiveFeed", run_model_with_live_feed)?;
     cx.export_function("readModel", encode_model)?;
+    cx.export_function("createExportCursor", create_export_cursor)?;
+    cx.export_function("nextExportChunk", next_export_chunk)?;
     cx.export_function("exportModel", export_csvs)?;
+    cx.export_function("startEventServer", start_event_server)?;
+    cx.export_function("stopEventServer", stop_event_server)?;
+    cx.export_function("snapshotModel", snapshot_model)?;
+    cx.export_function("restoreModel", restore_model)?;
     Ok(())
 }
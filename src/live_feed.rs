@@ -0,0 +1,139 @@
+//! Ingestion of a live flight-status feed — the kind of structured real-time data a transit
+//! status API exposes (scheduled vs. actual times, current delay, cancellation) — into
+//! corrections applied to an already-loaded `Model`'s `Flight`s. Its one caller,
+//! `run_model_with_live_feed`, applies a feed once, before the dispatcher loop starts; nothing
+//! applies further records while the run is in progress. Every correction it applies (a new
+//! delay, a cancellation, an observed actual time) is
+//! just another exogenous event for the dispatcher to recover from on its next pass over that
+//! flight, the same as a disruption.
+
+use std::cmp::max;
+use std::error::Error;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer};
+
+use crate::aircraft::FlightId;
+use crate::metrics::{CancelReason, DelayReason};
+use crate::model::Model;
+
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    DateTime::parse_from_rfc3339(&raw)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_optional_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: Option<String> = Option::deserialize(deserializer)?;
+    raw.map(|raw| {
+        DateTime::parse_from_rfc3339(&raw)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(serde::de::Error::custom)
+    })
+    .transpose()
+}
+
+/// One flight's reported status as of the feed's last poll.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlightStatusRecord {
+    pub flight_number: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub sched_depart: DateTime<Utc>,
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    pub actual_depart: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    pub actual_arrive: Option<DateTime<Utc>>,
+    /// Current departure delay against `sched_depart`, as forecast by the feed before an actual
+    /// departure is observed.
+    pub delay_minutes: i64,
+    pub cancelled: bool,
+}
+
+/// Applies a feed of `FlightStatusRecord`s to an already-loaded `Model`, matching each record to
+/// the scenario flight with the same `flight_number` and the closest `sched_depart` (a flight
+/// number can recur across days in a multi-day scenario).
+pub struct LiveFeedLoader;
+
+impl LiveFeedLoader {
+    /// Parse `feed` as newline-delimited JSON `FlightStatusRecord`s and apply each to `model` in
+    /// order.
+    pub fn ingest_str(model: &Model, feed: &str) -> Result<(), Box<dyn Error>> {
+        for line in feed.lines().filter(|line| !line.trim().is_empty()) {
+            let record: FlightStatusRecord = serde_json::from_str(line)?;
+            Self::apply(model, &record);
+        }
+        Ok(())
+    }
+
+    /// Apply a single status record. A no-op if no scenario flight matches
+    /// `record.flight_number`.
+    pub fn apply(model: &Model, record: &FlightStatusRecord) {
+        let Some(flight_id) = Self::matching_flight(model, record) else {
+            return;
+        };
+
+        if record.cancelled {
+            model.cancel_flight(
+                flight_id,
+                CancelReason::HeavyExpectedDelay(DelayReason::Disrupted(
+                    "cancelled by live status feed".to_string(),
+                )),
+            );
+            return;
+        }
+
+        let mut flight = model.flight_write(flight_id);
+        if flight.depart_time.is_none() {
+            if let Some(actual_depart) = record.actual_depart {
+                let observed_delay = max(TimeDelta::zero(), actual_depart - flight.sched_depart);
+                let additional = observed_delay - flight.dep_delay;
+                if additional > TimeDelta::zero() {
+                    flight.delay_departure(additional);
+                }
+                flight.takeoff(actual_depart);
+            } else if record.delay_minutes > 0 {
+                let forecast_delay = TimeDelta::minutes(record.delay_minutes);
+                let additional = forecast_delay - flight.dep_delay;
+                if additional > TimeDelta::zero() {
+                    flight.delay_departure(additional);
+                }
+            }
+        }
+        if flight.arrive_time.is_none() {
+            if let Some(actual_arrive) = record.actual_arrive {
+                let observed_delay = max(TimeDelta::zero(), actual_arrive - flight.sched_arrive);
+                let additional = observed_delay - flight.accum_delay.unwrap_or(TimeDelta::zero());
+                if additional > TimeDelta::zero() {
+                    flight.delay_arrival(additional);
+                }
+                flight.land(actual_arrive);
+            }
+        }
+    }
+
+    /// The scenario flight `record` describes: same `flight_number`, not yet cancelled, and
+    /// whose `sched_depart` is closest to the record's.
+    fn matching_flight(model: &Model, record: &FlightStatusRecord) -> Option<FlightId> {
+        model
+            .flights
+            .values()
+            .filter_map(|f| {
+                let flt = f.try_read().ok()?;
+                (flt.flight_number == record.flight_number && !flt.cancelled).then_some(flt.id)
+            })
+            .min_by_key(|id| {
+                (model.flight_read(*id).sched_depart - record.sched_depart)
+                    .num_seconds()
+                    .abs()
+            })
+    }
+}
@@ -1,19 +1,23 @@
 use std::{
     cmp::max,
     collections::{BTreeMap, HashMap},
+    io::Write,
     sync::{mpsc, Weak},
     thread::{self, JoinHandle},
 };
 
 use chrono::{DateTime, Duration, TimeDelta, Utc};
+use hdrhistogram::Histogram;
 
 use crate::{
     aircraft::FlightId,
     airport::AirportCode,
     crew::CrewId,
+    journey_metrics::{journey_delay_minutes, misconnected},
     model::Model,
 };
 
+#[derive(Clone)]
 pub struct ModelEvent {
     pub time: DateTime<Utc>,
     pub data: ModelEventType,
@@ -23,16 +27,52 @@ pub struct ModelEvent {
 pub enum DelayReason {
     CrewShortage,
     AircraftShortage,
+    /// A crew member considered for this flight would breach the flight-duty-period, rest, or
+    /// cumulative-flight-time limits in `model.config.crew_duty_engine` — distinct from
+    /// `CrewShortage`, which covers a crew member who is merely tied up or away from the origin
+    /// right now.
+    CrewIllegal(CrewId),
     Disrupted(String),
     RateLimited(AirportCode),
+    /// Held back by `Dispatcher`'s conflict graph: a higher-priority flight sharing this
+    /// flight's aircraft or crew hasn't resolved its own resource commitment yet.
+    ResourceConflict(FlightId),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CancelReason {
     HeavyExpectedDelay(DelayReason),
     DelayTimedOut,
 }
 
+/// A periodic snapshot of `Dispatcher`'s own scheduling activity over the interval since the
+/// previous snapshot (or since `SimulationStarted`, for the first one). Lets an operator see
+/// throughput and retry pressure — which resource class is the bottleneck, how the backlog grows
+/// — without hand-instrumenting every branch of `update_flight`, mirroring the counters Solana's
+/// `PrioGraphScheduler` added to `receive_completed`.
+#[derive(Debug, Clone, Default)]
+pub struct DispatcherStats {
+    /// `CheckDepart`/`CheckArrive` updates popped off `update_queue` and run through
+    /// `update_flight` this interval.
+    pub updates_processed: u64,
+    /// Flights that completed `depart_flight` this interval.
+    pub departed: u64,
+    /// Flights delayed this interval, split by cause.
+    pub delayed: HashMap<DelayReason, u64>,
+    /// `AircraftAssignmentChanged` events emitted this interval (greedy fallback, a configured
+    /// `AircraftSelectionStrategy`, and `batch_assign_aircraft` all count).
+    pub aircraft_reassignments: u64,
+    /// `CrewAssignmentChanged` events emitted this interval.
+    pub crew_reassignments: u64,
+    /// Times the naive per-flight greedy fallback selector ran (i.e. no `AircraftSelectionStrategy`
+    /// configured), rather than a pluggable strategy or `batch_assign_aircraft`.
+    pub fallback_selector_invocations: u64,
+    /// Flights cancelled this interval, split by cause.
+    pub cancelled: HashMap<CancelReason, u64>,
+    /// `update_queue` depth at the moment this snapshot was taken.
+    pub queue_depth: usize,
+}
+
 #[derive(Debug, Clone)]
 pub enum ModelEventType {
     SimulationStarted(Weak<Model>),
@@ -59,23 +99,73 @@ pub enum ModelEventType {
     CrewSelection(FlightId, Vec<CrewId>),
     AircraftSelection(FlightId, Option<String>),
 
+    // -- Resource backoff --
+    // Sender: Dispatcher
+    /// A flight has been re-delayed for lack of aircraft/crew enough times that its backoff
+    /// wait grew past the previous attempt: `(flight, attempt count, next wait)`.
+    ResourceWaitEscalated(FlightId, u32, Duration),
+
+    // -- Scheduler health --
+    // Sender: Dispatcher
+    DispatcherStats(DispatcherStats),
+
     // -- Completion --
     SimulationComplete,
 }
 
+/// Highest arrival/departure delay, in minutes, representable in the delay histograms.
+/// Anything beyond this is clamped into the top bucket rather than growing the histogram.
+const MAX_DELAY_MINUTES: u64 = 24 * 60;
+
+/// Standard percentile set plus mean/max for a delay histogram, suitable for a final report
+/// or export without requiring callers to depend on `hdrhistogram` directly.
+#[derive(Debug, Clone, Copy)]
+pub struct DelayPercentiles {
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub mean: f64,
+    pub max: u64,
+}
+
 pub struct MetricsProcessor {
     receiver: mpsc::Receiver<ModelEvent>,
     model: Weak<Model>,
     // more memory needed to compute KPIs
-    /// On-time performance measurement. Delays are stored in minutes
-    pub arrival_delays: Vec<u16>,
+    /// On-time performance measurement. Delay minutes are recorded into an HDR histogram so
+    /// percentile KPIs are O(1) to query regardless of flight count.
+    pub arrival_delay_hist: Histogram<u64>,
+    /// Departure delay distribution in minutes, recorded the same way as `arrival_delay_hist`.
+    pub departure_delay_hist: Histogram<u64>,
     /// (On-time flight count, total flight count, cancellation count)
     pub otp: BTreeMap<DateTime<Utc>, (u32, u32, u32)>,
 
-    /// Delay cause distribution (departure)
+    /// Delay cause distribution (departure): total minutes
     pub dep_delay_causes: HashMap<DelayReason, u32>,
-    /// Delay cause distribution (arrival)
+    /// Delay cause distribution (arrival): total minutes
     pub arr_delay_causes: HashMap<DelayReason, u32>,
+    /// Number of `FlightDepartureDelayed` events attributed to each cause (a flight delayed
+    /// more than once for the same reason is counted once per event, not once per flight)
+    pub dep_delay_counts: HashMap<DelayReason, u32>,
+    /// Number of `FlightArrivalDelayed` events attributed to each cause, see `dep_delay_counts`
+    pub arr_delay_counts: HashMap<DelayReason, u32>,
+    /// Cancellation count by `CancelReason`
+    pub cancellations: HashMap<CancelReason, u32>,
+
+    /// `DispatcherStats` snapshots, keyed by the time they were emitted, so an operator can chart
+    /// throughput/backlog evolution over the recovery window.
+    pub dispatcher_stats: BTreeMap<DateTime<Utc>, DispatcherStats>,
+
+    /// Total journey delay (minutes, summed across every leg flown) for each passenger group
+    /// that has completed its itinerary.
+    pub passenger_trip_delay_hist: Histogram<u64>,
+    /// Passenger count (not group count) that missed a connection somewhere along their route.
+    pub misconnected_passengers: u32,
+
+    /// Optional InfluxDB line-protocol sink (file, stdout, or a TCP/HTTP writer) that each
+    /// processed event is streamed to as it is handled, so a dashboard can observe a run live.
+    line_sink: Option<Box<dyn Write + Send>>,
+    line_buf: String,
 }
 
 // impl MapElement for Airport {
@@ -106,18 +196,61 @@ pub struct MetricsProcessor {
 
 
 impl MetricsProcessor {
-    pub fn new(receiver: mpsc::Receiver<ModelEvent>) -> JoinHandle<MetricsProcessor> {
+    /// Number of buffered line-protocol records to accumulate before flushing to `line_sink`.
+    const LINE_BATCH_FLUSH: usize = 64;
+
+    pub fn new(
+        receiver: mpsc::Receiver<ModelEvent>,
+        line_sink: Option<Box<dyn Write + Send>>,
+    ) -> JoinHandle<MetricsProcessor> {
         let proc = Self {
             receiver,
             model: Weak::new(),
-            arrival_delays: Vec::new(),
+            arrival_delay_hist: Histogram::new_with_max(MAX_DELAY_MINUTES, 3)
+                .expect("valid histogram configuration"),
+            departure_delay_hist: Histogram::new_with_max(MAX_DELAY_MINUTES, 3)
+                .expect("valid histogram configuration"),
             dep_delay_causes: HashMap::new(),
             arr_delay_causes: HashMap::new(),
+            dep_delay_counts: HashMap::new(),
+            arr_delay_counts: HashMap::new(),
+            cancellations: HashMap::new(),
+            dispatcher_stats: BTreeMap::new(),
+            passenger_trip_delay_hist: Histogram::new_with_max(MAX_DELAY_MINUTES, 3)
+                .expect("valid histogram configuration"),
+            misconnected_passengers: 0,
             otp: BTreeMap::new(),
+            line_sink,
+            line_buf: String::new(),
         };
         thread::spawn(move || proc.run())
     }
 
+    /// Standard percentile set plus mean/max for the arrival delay distribution.
+    pub fn arrival_delay_stats(&self) -> DelayPercentiles {
+        Self::percentiles(&self.arrival_delay_hist)
+    }
+
+    /// Standard percentile set plus mean/max for the departure delay distribution.
+    pub fn departure_delay_stats(&self) -> DelayPercentiles {
+        Self::percentiles(&self.departure_delay_hist)
+    }
+
+    /// Standard percentile set plus mean/max for completed passenger-journey delay.
+    pub fn passenger_trip_delay_stats(&self) -> DelayPercentiles {
+        Self::percentiles(&self.passenger_trip_delay_hist)
+    }
+
+    fn percentiles(hist: &Histogram<u64>) -> DelayPercentiles {
+        DelayPercentiles {
+            p50: hist.value_at_quantile(0.50),
+            p90: hist.value_at_quantile(0.90),
+            p99: hist.value_at_quantile(0.99),
+            mean: hist.mean(),
+            max: hist.max(),
+        }
+    }
+
     fn run(mut self) -> MetricsProcessor {
         loop {
             let Ok(event) = self.receiver.recv() else {
@@ -126,23 +259,14 @@ impl MetricsProcessor {
             };
             match event.data {
                 ModelEventType::SimulationComplete => {
-                    // TODO write data
-                    // let model = self.model.upgrade().unwrap();
-                    // let intervals = model.flights.iter()
-                    //     .map(|(id, flight)| (id, flight.read().unwrap()))
-                    //     .filter(|(_, flight)| !flight.cancelled)
-                    //     .map(|(id, flight)| Interval {
-                    //         start: flight.depart_time.unwrap().timestamp() as u64,
-                    //         stop: flight.arrive_time.unwrap().timestamp() as u64,
-                    //         val: *id
-                    //     })
-                    //     .collect::<Vec<_>>();
+                    // Everything the disruption/cancellation report needs (cause distributions,
+                    // cancellations, OTP curve) is already aggregated above; `export_report`
+                    // writes it out from the returned `MetricsProcessor` once this thread joins.
+                    self.flush_line_sink();
                     return self;
                 }
                 ModelEventType::SimulationStarted(model) => {
                     self.model = model;
-                    let mdl = self.model.upgrade().unwrap();
-                    self.arrival_delays.reserve(mdl.flights.len());
                     continue;
                 }
                 _ => {
@@ -152,9 +276,130 @@ impl MetricsProcessor {
 
             self.track_otp(&event);
             self.track_delay_causes(&event);
+            self.track_cancellations(&event);
+            self.track_delay_histograms(&event);
+            self.track_passenger_delivery(&event);
+            self.track_dispatcher_stats(&event);
+            self.write_line_protocol(&event);
+        }
+    }
+
+    /// Record arrival/departure delay minutes into the HDR histograms, clamped to a
+    /// non-negative value within `MAX_DELAY_MINUTES`.
+    fn track_delay_histograms(&mut self, event: &ModelEvent) {
+        let Some(mdl) = self.model.upgrade() else {
+            return;
+        };
+        match event.data {
+            ModelEventType::FlightArrived(id) => {
+                let flt = mdl.flight_read(id);
+                let delay = max(TimeDelta::zero(), event.time - flt.sched_arrive).num_minutes();
+                let _ = self
+                    .arrival_delay_hist
+                    .record(delay.clamp(0, MAX_DELAY_MINUTES as i64) as u64);
+            }
+            ModelEventType::FlightDeparted(id) => {
+                let flt = mdl.flight_read(id);
+                let delay = max(TimeDelta::zero(), flt.dep_delay).num_minutes();
+                let _ = self
+                    .departure_delay_hist
+                    .record(delay.clamp(0, MAX_DELAY_MINUTES as i64) as u64);
+            }
+            _ => {}
         }
     }
 
+    /// On `FlightArrived`, record the trip delay and misconnection status of every passenger
+    /// group whose itinerary ends at this flight's destination, i.e., whose journey is now
+    /// complete. `flt.passengers` still holds exactly who boarded this leg: `mark_arrival`
+    /// copies completing groups' history but never clears it.
+    fn track_passenger_delivery(&mut self, event: &ModelEvent) {
+        let ModelEventType::FlightArrived(id) = event.data else {
+            return;
+        };
+        let Some(mdl) = self.model.upgrade() else {
+            return;
+        };
+        let flt = mdl.flight_read(id);
+        for demand in &flt.passengers {
+            if demand.path.last() != Some(&flt.dest) {
+                continue;
+            }
+            let delay = journey_delay_minutes(&mdl, &demand.flights_taken);
+            let _ = self.passenger_trip_delay_hist.record_n(
+                delay.clamp(0, MAX_DELAY_MINUTES as i64) as u64,
+                demand.count as u64,
+            );
+            if misconnected(&mdl, &demand.flights_taken) {
+                self.misconnected_passengers += demand.count;
+            }
+        }
+    }
+
+    /// Append an InfluxDB line-protocol record for `event` to the buffered sink, if one is
+    /// configured, flushing once the buffer grows past `LINE_BATCH_FLUSH` records.
+    fn write_line_protocol(&mut self, event: &ModelEvent) {
+        if self.line_sink.is_none() {
+            return;
+        }
+        let ts = event.time.timestamp_nanos_opt().unwrap_or_default();
+        match &event.data {
+            ModelEventType::FlightArrived(id) => {
+                let Some(mdl) = self.model.upgrade() else {
+                    return;
+                };
+                let flt = mdl.flight_read(*id);
+                let delay = max(TimeDelta::zero(), event.time - flt.sched_arrive).num_minutes();
+                self.line_buf.push_str(&format!(
+                    "flight_arrival,origin={},dest={},tail={} delay_min={}i,on_time={}i {}\n",
+                    flt.origin,
+                    flt.dest,
+                    flt.aircraft_tail.as_deref().unwrap_or("unknown"),
+                    delay,
+                    if delay <= 15 { 1 } else { 0 },
+                    ts
+                ));
+            }
+            ModelEventType::FlightDepartureDelayed(_id, duration, reason) => {
+                self.line_buf.push_str(&format!(
+                    "delay,reason={:?},phase=dep minutes={}i {}\n",
+                    reason,
+                    duration.num_minutes(),
+                    ts
+                ));
+            }
+            ModelEventType::FlightArrivalDelayed(_id, duration, reason) => {
+                self.line_buf.push_str(&format!(
+                    "delay,reason={:?},phase=arr minutes={}i {}\n",
+                    reason,
+                    duration.num_minutes(),
+                    ts
+                ));
+            }
+            _ => return,
+        }
+        if let Some((on_time, total, cancelled)) = self.otp.last_key_value().map(|(_, v)| *v) {
+            self.line_buf.push_str(&format!(
+                "otp on_time={}i,total={}i,cancelled={}i {}\n",
+                on_time, total, cancelled, ts
+            ));
+        }
+        if self.line_buf.matches('\n').count() >= Self::LINE_BATCH_FLUSH {
+            self.flush_line_sink();
+        }
+    }
+
+    fn flush_line_sink(&mut self) {
+        if self.line_buf.is_empty() {
+            return;
+        }
+        if let Some(sink) = self.line_sink.as_mut() {
+            let _ = sink.write_all(self.line_buf.as_bytes());
+            let _ = sink.flush();
+        }
+        self.line_buf.clear();
+    }
+
     fn track_otp(&mut self, event: &ModelEvent) {
         if let ModelEventType::FlightArrived(id) = event.data {
             let Some(mdl) = self.model.upgrade() else {
@@ -164,7 +409,6 @@ impl MetricsProcessor {
             // println!("[{}] {:?} ({}, {} from {} to {} with {} passengers, piloted by {})",
             //     event.time, event.data, &flt.flight_number, &flt.aircraft_tail, &flt.origin, &flt.dest, flt.passengers.iter().map(|i| i.count).sum::<u32>(), flt.crew[0]);
             let delay = max(TimeDelta::zero(), event.time - flt.sched_arrive);
-            self.arrival_delays.push(delay.num_minutes() as u16);
 
             let mut prev = self.otp
                 .last_key_value()
@@ -206,9 +450,64 @@ impl MetricsProcessor {
         if let ModelEventType::FlightArrivalDelayed(_id, duration, reason) = &event.data {
             *self.arr_delay_causes.entry(reason.clone()).or_insert(0) +=
                 duration.num_minutes() as u32;
+            *self.arr_delay_counts.entry(reason.clone()).or_insert(0) += 1;
         } else if let ModelEventType::FlightDepartureDelayed(_id, duration, reason) = &event.data {
             *self.dep_delay_causes.entry(reason.clone()).or_insert(0) +=
                 duration.num_minutes() as u32;
+            *self.dep_delay_counts.entry(reason.clone()).or_insert(0) += 1;
+        }
+    }
+
+    fn track_cancellations(&mut self, event: &ModelEvent) {
+        if let ModelEventType::FlightCancelled(_id, reason) = &event.data {
+            *self.cancellations.entry(reason.clone()).or_insert(0) += 1;
+        }
+    }
+
+    fn track_dispatcher_stats(&mut self, event: &ModelEvent) {
+        if let ModelEventType::DispatcherStats(stats) = &event.data {
+            self.dispatcher_stats.insert(event.time, stats.clone());
+        }
+    }
+}
+
+impl std::ops::Add for MetricsProcessor {
+    type Output = MetricsProcessor;
+
+    /// Merge the delay histograms, cause distributions, and OTP curve of two finished runs,
+    /// so parallel scenario executions can be combined into one aggregate report.
+    fn add(mut self, rhs: MetricsProcessor) -> MetricsProcessor {
+        self.arrival_delay_hist
+            .add(&rhs.arrival_delay_hist)
+            .expect("incompatible histogram configuration");
+        self.departure_delay_hist
+            .add(&rhs.departure_delay_hist)
+            .expect("incompatible histogram configuration");
+        self.passenger_trip_delay_hist
+            .add(&rhs.passenger_trip_delay_hist)
+            .expect("incompatible histogram configuration");
+        self.misconnected_passengers += rhs.misconnected_passengers;
+        for (reason, minutes) in rhs.dep_delay_causes {
+            *self.dep_delay_causes.entry(reason).or_insert(0) += minutes;
+        }
+        for (reason, minutes) in rhs.arr_delay_causes {
+            *self.arr_delay_causes.entry(reason).or_insert(0) += minutes;
+        }
+        for (reason, count) in rhs.dep_delay_counts {
+            *self.dep_delay_counts.entry(reason).or_insert(0) += count;
+        }
+        for (reason, count) in rhs.arr_delay_counts {
+            *self.arr_delay_counts.entry(reason).or_insert(0) += count;
+        }
+        for (reason, count) in rhs.cancellations {
+            *self.cancellations.entry(reason).or_insert(0) += count;
+        }
+        for (time, (on_time, total, cancelled)) in rhs.otp {
+            let entry = self.otp.entry(time).or_insert((0, 0, 0));
+            entry.0 += on_time;
+            entry.1 += total;
+            entry.2 += cancelled;
         }
+        self
     }
 }
@@ -2,21 +2,55 @@ use crate::{
     aircraft::{Aircraft, Flight, FlightId},
     airport::{Airport, AirportCode, Clearance, Disruption, DisruptionIndex},
     crew::{Crew, CrewId},
+    duty_rules::Far117LikeEngine,
     metrics::{CancelReason, MetricsProcessor, ModelEvent, ModelEventType},
 };
 use chrono::{DateTime, TimeDelta, Utc};
 use neon::types::Finalize;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     sync::{mpsc, Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
     thread::JoinHandle,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
     pub crew_turnaround_time: TimeDelta,
     pub aircraft_turnaround_time: TimeDelta,
     pub max_delay: TimeDelta,
+    /// Nodes kept per expansion depth by `strategies::new_for_aircraft("beam")`'s graph search.
+    /// `"astar"` passes `u32::MAX` here so nothing is pruned.
+    pub aircraft_search_beam_width: u32,
+    /// Depth cap for the `"astar"`/`"beam"` graph-search backends, replacing the DFS backend's
+    /// hardcoded 4-leg limit.
+    pub aircraft_search_max_depth: u32,
+    /// Maximum total duty time `strategies::new_for_crew("reserve")` will assign a crew member
+    /// before requiring `crew_min_rest` off.
+    pub crew_max_duty: TimeDelta,
+    /// Minimum rest a crew member freed by a cancellation must accrue before `"reserve"`
+    /// considers their duty clock reset and reassigns them.
+    pub crew_min_rest: TimeDelta,
+    /// Search objective key (`"coverage"`, `"total_delay"`, `"latest_arrival"`) parameterizing
+    /// node/chain scoring in `strategies::new_for_aircraft`'s `"dfs"`/`"astar"`/`"beam"`/
+    /// `"optimal"` backends.
+    pub aircraft_search_objective: String,
+    /// Maximum number of empty repositioning (ferry) legs `strategies::new_for_aircraft("dfs")`
+    /// may fly a single surplus aircraft within one search path.
+    pub aircraft_max_ferry_legs: u32,
+    /// Cumulative ferry flight time `"dfs"` may spend repositioning a single surplus aircraft
+    /// within one search path.
+    pub aircraft_max_ferry_duration: TimeDelta,
+    /// Per-minute penalty, on top of the 1:1 delay cost, charged by `assignment_cost::insertion_cost`
+    /// against the part of a candidate's wait that falls beyond `max_delay`.
+    pub assignment_window_violation_weight: f64,
+    /// Flat penalty `assignment_cost::insertion_cost` adds for a candidate that isn't already on
+    /// the ground at the flight's origin (i.e. is still inbound and would need to connect in).
+    pub assignment_deadhead_penalty: f64,
+    /// Flight-duty-period/rest/cumulative-flight-time rules `Crew::legal_for` checks before a
+    /// crew member can be assigned a flight, parameterized so different scenarios can simulate
+    /// different carriers' duty rulesets.
+    pub crew_duty_engine: Far117LikeEngine,
 }
 
 // Model should never be mutably borrowed as it needs to be borrowed practically everywhere
@@ -29,6 +63,9 @@ pub struct Model {
     pub flights: HashMap<FlightId, Arc<RwLock<Flight>>>,
     pub disruptions: DisruptionIndex,
     pub publisher: mpsc::Sender<ModelEvent>,
+    /// Tee of every event sent on `publisher`, consumed by the gRPC `EventService` so
+    /// external dashboards/controllers can watch the simulation live.
+    pub event_broadcast: tokio::sync::broadcast::Sender<ModelEvent>,
 
     pub metrics: RwLock<Option<JoinHandle<MetricsProcessor>>>,
     pub config: ModelConfig,
@@ -85,7 +122,12 @@ impl Model {
         }
         flight.takeoff(now);
         let mut origin = self.airports.get(&flight.origin).unwrap().write().unwrap();
-        origin.mark_departure(self.now(), &mut flight, aircraft.type_.1);
+        origin.mark_departure(
+            self.now(),
+            &mut flight,
+            [aircraft.type_.1 as u32, aircraft.cargo_capacity],
+            self,
+        );
         send_event!(self, ModelEventType::FlightDeparted(flight_id));
     }
 
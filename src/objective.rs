@@ -0,0 +1,126 @@
+//! Scalar objective functions over a model's final state, so alternative disruption/recovery
+//! policies can be ranked by a single number instead of comparing raw metrics by hand.
+//!
+//! Unlike `MetricsProcessor` (which aggregates from the event stream as the simulation runs),
+//! these walk the finished model directly: every arrived flight's `passengers` for delivered
+//! groups, and every airport's `passengers` for groups stranded short of their final stop.
+//! Callers should only score a model after its run has reached `model.end`.
+
+use std::cmp::max;
+
+use chrono::TimeDelta;
+
+use crate::model::Model;
+
+/// Minutes charged against a passenger group that never reached its final destination, standing
+/// in for a delay that can't otherwise be measured because there's no arrival time to compare.
+const UNDELIVERED_PENALTY_MINUTES: f64 = 24.0 * 60.0;
+
+/// Below this gap between landing on one leg and departing on the next, a passenger group is
+/// considered to have connected normally; above it, they missed their intended connection.
+/// Mirrors `journey_metrics::MISCONNECT_THRESHOLD_MINUTES`.
+const MISCONNECT_THRESHOLD_MINUTES: i64 = 60;
+
+/// A scalar objective computed from a model's final state. Lower is better; a search or tuning
+/// process comparing disruption/recovery policies should prefer the run with the lowest score.
+pub trait RecoveryObjective {
+    fn score(&self, model: &Model) -> f64;
+}
+
+/// Total arrival delay, in minutes, summed across every passenger who reached their final
+/// destination, plus `UNDELIVERED_PENALTY_MINUTES` per passenger who never did.
+pub struct MinimizePassengerDelay;
+
+impl RecoveryObjective for MinimizePassengerDelay {
+    fn score(&self, model: &Model) -> f64 {
+        let mut total_minutes = 0.0;
+        for flight in model.flights.values() {
+            let flt = flight.read().unwrap();
+            if flt.cancelled || flt.arrive_time.is_none() {
+                continue;
+            }
+            for demand in &flt.passengers {
+                if demand.path.last() != Some(&flt.dest) {
+                    continue;
+                }
+                let delay = max(TimeDelta::zero(), flt.arrive_time.unwrap() - flt.sched_arrive)
+                    .num_minutes() as f64;
+                total_minutes += delay * demand.count as f64;
+            }
+        }
+        for airport in model.airports.values() {
+            for demand in &airport.read().unwrap().passengers {
+                total_minutes += UNDELIVERED_PENALTY_MINUTES * demand.count as f64;
+            }
+        }
+        total_minutes
+    }
+}
+
+/// Count of passengers (not groups) who missed an intended connection somewhere along their
+/// route, i.e. whose gap between landing on one leg and departing the next exceeded
+/// `MISCONNECT_THRESHOLD_MINUTES`.
+pub struct MinimizeMisconnects;
+
+impl RecoveryObjective for MinimizeMisconnects {
+    fn score(&self, model: &Model) -> f64 {
+        let mut misconnected_count = 0u32;
+        for flight in model.flights.values() {
+            let flt = flight.read().unwrap();
+            if flt.cancelled || flt.arrive_time.is_none() {
+                continue;
+            }
+            for demand in &flt.passengers {
+                if demand.path.last() == Some(&flt.dest) && Self::misconnected(model, &demand.flights_taken) {
+                    misconnected_count += demand.count;
+                }
+            }
+        }
+        misconnected_count as f64
+    }
+}
+
+impl MinimizeMisconnects {
+    fn misconnected(model: &Model, flights_taken: &[crate::aircraft::FlightId]) -> bool {
+        flights_taken.windows(2).any(|pair| {
+            let prev = model.flight_read(pair[0]);
+            let next = model.flight_read(pair[1]);
+            matches!(
+                (prev.arrive_time, next.depart_time),
+                (Some(arrive), Some(depart)) if (depart - arrive).num_minutes() > MISCONNECT_THRESHOLD_MINUTES
+            )
+        })
+    }
+}
+
+/// Several objectives combined into one score via a weighted sum, so e.g. passenger delay and
+/// misconnections can be optimized together without one dominating the other by raw magnitude.
+pub struct CompositeObjective {
+    weighted: Vec<(f64, Box<dyn RecoveryObjective>)>,
+}
+
+impl CompositeObjective {
+    pub fn new() -> Self {
+        Self { weighted: Vec::new() }
+    }
+
+    pub fn with(mut self, weight: f64, objective: Box<dyn RecoveryObjective>) -> Self {
+        self.weighted.push((weight, objective));
+        self
+    }
+}
+
+impl Default for CompositeObjective {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecoveryObjective for CompositeObjective {
+    fn score(&self, model: &Model) -> f64 {
+        self.weighted
+            .iter()
+            .map(|(weight, objective)| weight * objective.score(model))
+            .sum()
+    }
+}
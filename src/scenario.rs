@@ -1,4 +1,11 @@
 //! Defines adapters for constructing scenarios from external sources.
+//!
+//! `ScenarioSource` is the backend-neutral half: it produces typed rows for each entity
+//! (airports, aircraft, crew, flights, demand, disruptions, config) without knowing anything
+//! about `Model`/`Dispatcher` assembly. `assemble_model`/`assemble_dispatcher` do that
+//! assembly once, generically, and a blanket `ScenarioLoader` impl wires the two together so
+//! any `ScenarioSource` (SQLite today; Parquet, Postgres, ... tomorrow) gets `read_model`/
+//! `read_dispatcher` for free.
 
 use std::{
     collections::{BinaryHeap, HashMap, HashSet},
@@ -11,11 +18,15 @@ use rusqlite::Connection;
 use crate::{
     aircraft::{Aircraft, Flight, FlightId},
     airport::{
-        Airport, AirportCode, CumulativeSmallSlotManager, DepartureRateLimit, Disruption, DisruptionIndex, GroundDelayProgram, PassengerDemand, SlotManager
+        Airport, AirportCode, CumulativeSmallSlotManager, DepartureRateLimit, Disruption,
+        DisruptionIndex, GdpMode, GroundDelayProgram, PassengerDemand, TokenBucket,
     },
+    conflict_graph::PrioGraph,
     crew::{Crew, CrewId},
     dispatcher::{strategies, Dispatcher},
-    metrics::MetricsProcessor,
+    duty_rules::Far117LikeEngine,
+    grpc::EventHub,
+    metrics::{DispatcherStats, MetricsProcessor},
     model::{Model, ModelConfig},
 };
 
@@ -24,6 +35,381 @@ pub trait ScenarioLoader<E: std::fmt::Debug> {
     fn read_dispatcher(&self, model: Arc<Model>) -> Result<Dispatcher, E>;
 }
 
+// MARK: Backend-neutral row source
+
+/// One row per airport: code and hourly departure/arrival rate caps.
+pub struct AirportRow {
+    pub code: AirportCode,
+    pub max_dep_per_hour: u32,
+    pub max_arr_per_hour: u32,
+}
+
+/// One row per aircraft in the fleet, with its starting location.
+pub struct AircraftRow {
+    pub tail: String,
+    pub location: AirportCode,
+    pub typename: String,
+    pub capacity: u16,
+    /// See `Aircraft::cargo_capacity`.
+    pub cargo_capacity: u32,
+}
+
+/// One row per crew member, with their starting location.
+pub struct CrewRow {
+    pub id: CrewId,
+    pub location: AirportCode,
+}
+
+/// One row per scheduled flight, including its piloting and deadheading crew.
+pub struct FlightRow {
+    pub id: FlightId,
+    pub flight_number: String,
+    pub aircraft_tail: Option<String>,
+    pub origin: AirportCode,
+    pub dest: AirportCode,
+    pub pilot: Option<CrewId>,
+    pub deadheaders: Vec<CrewId>,
+    pub sched_depart: DateTime<Utc>,
+    pub sched_arrive: DateTime<Utc>,
+}
+
+/// One row per passenger demand group's itinerary.
+pub struct DemandRow {
+    pub path: Vec<AirportCode>,
+    pub count: u32,
+    /// See `PassengerDemand::bag_weight`.
+    pub bag_weight: u32,
+}
+
+/// One row per hour of a disruption's active window. Backends must yield these ordered by
+/// `(site, kind, start)` so `assemble_model` can merge consecutive hours of the same
+/// disruption into a single `CumulativeSmallSlotManager`, exactly as `SqliteScenarioLoader`'s
+/// SQL `ORDER BY airport, type, start` did.
+pub struct DisruptionRow {
+    pub site: AirportCode,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub hourly_rate: u32,
+    pub kind: String,
+    pub reason: String,
+}
+
+/// Scenario-wide configuration: simulation window, tuning knobs, and the dispatcher's
+/// resource-selection behavior.
+pub struct ScenarioConfigRow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub crew_turnaround_time: TimeDelta,
+    pub aircraft_turnaround_time: TimeDelta,
+    pub max_delay: TimeDelta,
+    pub aircraft_selector: Option<String>,
+    pub crew_selector: Option<String>,
+    pub wait_for_deadheaders: bool,
+    pub aircraft_reassign_tolerance: TimeDelta,
+    pub crew_reassign_tolerance: TimeDelta,
+    /// Beam width and depth cap for the `"astar"`/`"beam"` `aircraft_selector` graph search;
+    /// ignored by `"giveup"`/`"dfs"`.
+    pub aircraft_search_beam_width: u32,
+    pub aircraft_search_max_depth: u32,
+    /// Duty/rest limits for the `"reserve"` `crew_selector`; ignored by `"giveup"`.
+    pub crew_max_duty: TimeDelta,
+    pub crew_min_rest: TimeDelta,
+    /// Search objective key for `aircraft_selector`'s DFS/graph-search/optimal backends; see
+    /// `ModelConfig::aircraft_search_objective`.
+    pub aircraft_search_objective: String,
+    /// Ferry-leg budget for the `"dfs"` `aircraft_selector`; ignored by every other backend. See
+    /// `ModelConfig::aircraft_max_ferry_legs`/`aircraft_max_ferry_duration`.
+    pub aircraft_max_ferry_legs: u32,
+    pub aircraft_max_ferry_duration: TimeDelta,
+    /// Weights for `assignment_cost::insertion_cost`, used by the dispatcher's fallback
+    /// aircraft/crew selectors when no pluggable selector is configured.
+    pub assignment_window_violation_weight: f64,
+    pub assignment_deadhead_penalty: f64,
+    /// See `ModelConfig::crew_duty_engine`.
+    pub crew_duty_engine: Far117LikeEngine,
+    /// See `Dispatcher::batch_assign`.
+    pub batch_assign: bool,
+    /// Key selecting a `strategies::new_batch_assign_cost` sort key; ignored unless `batch_assign`
+    /// is set.
+    pub batch_assign_cost: String,
+    /// Whether `assemble_dispatcher` builds a `conflict_graph::PrioGraph` for the scenario. See
+    /// `Dispatcher::conflict_graph`.
+    pub conflict_graph_enabled: bool,
+    /// See `Dispatcher::resource_wait_base`/`resource_wait_cap`/`max_resource_wait`.
+    pub resource_wait_base: TimeDelta,
+    pub resource_wait_cap: TimeDelta,
+    pub max_resource_wait: TimeDelta,
+    /// Path `assemble_model` opens (append mode, creating it if needed) as
+    /// `MetricsProcessor`'s InfluxDB line-protocol sink; `None` runs with line-protocol export
+    /// disabled. See `MetricsProcessor::line_sink`.
+    pub line_sink_path: Option<String>,
+}
+
+/// A backend-neutral source of scenario rows. Implement this for a new storage system (Parquet,
+/// Postgres, ...) and `ScenarioLoader` (and therefore `read_model`/`read_dispatcher`) come for
+/// free via the blanket impl below; the assembly logic in `assemble_model`/`assemble_dispatcher`
+/// never needs to be reimplemented per backend.
+pub trait ScenarioSource {
+    type Error: std::fmt::Debug + From<std::io::Error>;
+
+    fn config(&self) -> Result<ScenarioConfigRow, Self::Error>;
+    fn airports(&self) -> Result<Vec<AirportRow>, Self::Error>;
+    fn aircraft(&self) -> Result<Vec<AircraftRow>, Self::Error>;
+    fn crew(&self) -> Result<Vec<CrewRow>, Self::Error>;
+    fn flights(&self) -> Result<Vec<FlightRow>, Self::Error>;
+    fn demand(&self) -> Result<Vec<DemandRow>, Self::Error>;
+    fn disruptions(&self) -> Result<Vec<DisruptionRow>, Self::Error>;
+}
+
+impl<S: ScenarioSource> ScenarioLoader<S::Error> for S {
+    fn read_model(&self) -> Result<Model, S::Error> {
+        assemble_model(self)
+    }
+
+    fn read_dispatcher(&self, model: Arc<Model>) -> Result<Dispatcher, S::Error> {
+        assemble_dispatcher(self, model)
+    }
+}
+
+fn assemble_model<S: ScenarioSource + ?Sized>(source: &S) -> Result<Model, S::Error> {
+    let config = source.config()?;
+    // The model publishes into the hub; the hub tees each event on to the metrics
+    // thread (unchanged) and to the gRPC broadcast channel for live subscribers.
+    let (tx, hub_rx) = mpsc::channel();
+    let (metrics_tx, metrics_rx) = mpsc::channel();
+    let (_hub_handle, hub) = EventHub::spawn(hub_rx, metrics_tx);
+    let line_sink: Option<Box<dyn std::io::Write + Send>> = match &config.line_sink_path {
+        Some(path) => Some(Box::new(
+            std::fs::OpenOptions::new().create(true).append(true).open(path)?,
+        )),
+        None => None,
+    };
+    let mut model = Model {
+        airports: HashMap::new(),
+        fleet: HashMap::new(),
+        crew: HashMap::new(),
+        flights: HashMap::new(),
+        disruptions: DisruptionIndex::new(),
+        _now: Arc::new(RwLock::new(config.start)),
+        end: config.end,
+        publisher: tx,
+        event_broadcast: hub.broadcast,
+        metrics: RwLock::new(Some(MetricsProcessor::new(metrics_rx, line_sink))),
+        config: ModelConfig {
+            crew_turnaround_time: config.crew_turnaround_time,
+            aircraft_turnaround_time: config.aircraft_turnaround_time,
+            max_delay: config.max_delay,
+            aircraft_search_beam_width: config.aircraft_search_beam_width,
+            aircraft_search_max_depth: config.aircraft_search_max_depth,
+            crew_max_duty: config.crew_max_duty,
+            crew_min_rest: config.crew_min_rest,
+            aircraft_search_objective: config.aircraft_search_objective.clone(),
+            aircraft_max_ferry_legs: config.aircraft_max_ferry_legs,
+            aircraft_max_ferry_duration: config.aircraft_max_ferry_duration,
+            assignment_window_violation_weight: config.assignment_window_violation_weight,
+            assignment_deadhead_penalty: config.assignment_deadhead_penalty,
+            crew_duty_engine: config.crew_duty_engine.clone(),
+        },
+    };
+
+    for row in source.airports()? {
+        model.airports.insert(
+            row.code,
+            Arc::new(RwLock::new(Airport {
+                code: row.code,
+                fleet: HashSet::new(),
+                crew: HashSet::new(),
+                passengers: Vec::new(),
+                max_arr_per_hour: row.max_arr_per_hour,
+                max_dep_per_hour: row.max_dep_per_hour,
+                departure_bucket: TokenBucket::new(row.max_dep_per_hour, model.now()),
+                arrival_bucket: TokenBucket::new(row.max_arr_per_hour, model.now()),
+            })),
+        );
+    }
+
+    for row in source.aircraft()? {
+        model.fleet.insert(
+            row.tail.clone(),
+            Arc::new(RwLock::new(Aircraft::new(
+                row.tail.clone(),
+                row.location,
+                &model.now(),
+                row.typename,
+                row.capacity,
+                row.cargo_capacity,
+            ))),
+        );
+        model.airports[&row.location]
+            .write()
+            .unwrap()
+            .fleet
+            .insert(row.tail);
+    }
+
+    for row in source.crew()? {
+        model.crew.insert(
+            row.id,
+            Arc::new(RwLock::new(Crew::new(row.id, row.location, model.now()))),
+        );
+        model.airports[&row.location]
+            .write()
+            .unwrap()
+            .crew
+            .insert(row.id);
+    }
+
+    for row in source.flights()? {
+        let mut crew = row.pilot.map(|i| vec![i]).unwrap_or_default();
+        crew.extend(row.deadheaders);
+        let flight = Flight {
+            id: row.id,
+            flight_number: row.flight_number,
+            aircraft_tail: row.aircraft_tail,
+            origin: row.origin,
+            dest: row.dest,
+            crew,
+            passengers: Vec::new(),
+            cancelled: false,
+            depart_time: None,
+            arrive_time: None,
+            dep_delay: TimeDelta::zero(),
+            accum_delay: None,
+            sched_depart: row.sched_depart,
+            sched_arrive: row.sched_arrive,
+        };
+        model.flights.insert(row.id, Arc::new(RwLock::new(flight)));
+    }
+
+    for row in source.demand()? {
+        if row.count == 0 {
+            continue;
+        }
+        let origin = row.path[0];
+        let demand = PassengerDemand {
+            path: row.path,
+            count: row.count,
+            flights_taken: Vec::new(),
+            bag_weight: row.bag_weight,
+        };
+        model.airports[&origin]
+            .write()
+            .unwrap()
+            .passengers
+            .push(demand);
+    }
+
+    for disruption in build_disruptions(source.disruptions()?) {
+        model.disruptions.add_disruption(disruption);
+    }
+
+    Ok(model)
+}
+
+fn assemble_dispatcher<S: ScenarioSource + ?Sized>(
+    source: &S,
+    model: Arc<Model>,
+) -> Result<Dispatcher, S::Error> {
+    let config = source.config()?;
+    let conflict_graph = config.conflict_graph_enabled.then(|| PrioGraph::build(&model));
+    Ok(Dispatcher {
+        model,
+        aircraft_selector: config.aircraft_selector.map(|s| {
+            strategies::new_for_aircraft(
+                &s,
+                config.aircraft_search_beam_width,
+                config.aircraft_search_max_depth,
+                &config.aircraft_search_objective,
+                config.aircraft_max_ferry_legs,
+                config.aircraft_max_ferry_duration,
+            )
+        }),
+        crew_selector: config.crew_selector.map(|s| {
+            strategies::new_for_crew(&s, config.crew_max_duty, config.crew_min_rest)
+        }),
+        wait_for_deadheaders: config.wait_for_deadheaders,
+        aircraft_tolerance_before_reassign: config.aircraft_reassign_tolerance,
+        use_fallback_aircraft_selector: true, // TODO add adjuster
+        crew_tolerance_before_reassign: config.crew_reassign_tolerance,
+        update_queue: BinaryHeap::new(),
+        aircraft_reassigned: HashSet::new(),
+        batch_assign: config.batch_assign,
+        batch_assign_cost: strategies::new_batch_assign_cost(&config.batch_assign_cost),
+        conflict_graph,
+        resource_wait_base: config.resource_wait_base,
+        resource_wait_cap: config.resource_wait_cap,
+        max_resource_wait: config.max_resource_wait,
+        resource_backoff: HashMap::new(),
+        stats_interval: TimeDelta::hours(1), // TODO add adjuster
+        stats: DispatcherStats::default(),
+        next_stats_emit: None,
+    })
+}
+
+/// Merge consecutive same-site, same-kind, contiguous-hour `DisruptionRow`s (as produced by a
+/// `(site, kind, start)`-ordered `ScenarioSource::disruptions`) into one `Disruption` each, the
+/// same grouping `SqliteScenarioLoader` always did by hand over its SQL rows.
+fn build_disruptions(rows: Vec<DisruptionRow>) -> Vec<Arc<RwLock<dyn Disruption>>> {
+    let mut disruptions = Vec::new();
+    let mut rows = rows.into_iter();
+    let Some(first) = rows.next() else {
+        return disruptions;
+    };
+
+    let mut site = first.site;
+    let mut kind = first.kind;
+    let mut reason = first.reason;
+    let mut start = first.start;
+    let mut end = first.end;
+    let mut rates: Vec<u32> =
+        std::iter::repeat(first.hourly_rate).take((end - start).num_hours() as usize).collect();
+
+    for row in rows {
+        if row.site != site || row.kind != kind || row.start != end {
+            disruptions.push(build_disruption(site, kind.clone(), start, reason.clone(), rates));
+            rates = Vec::new();
+            site = row.site;
+            kind = row.kind;
+            start = row.start;
+            reason = row.reason;
+        } else {
+            reason = row.reason;
+        }
+        end = row.end;
+        rates.extend(std::iter::repeat(row.hourly_rate).take((row.end - row.start).num_hours() as usize));
+    }
+    disruptions.push(build_disruption(site, kind, start, reason, rates));
+
+    disruptions
+}
+
+fn build_disruption(
+    site: AirportCode,
+    kind: String,
+    start: DateTime<Utc>,
+    reason: String,
+    rates: Vec<u32>,
+) -> Arc<RwLock<dyn Disruption>> {
+    let slots = CumulativeSmallSlotManager::<FlightId>::new(start, rates);
+    match kind.as_str() {
+        "gdp" => Arc::new(RwLock::new(GroundDelayProgram {
+            site,
+            slots,
+            reason: Some(reason),
+            mode: GdpMode::Greedy,
+            rbs_queue: RwLock::new(Vec::new()),
+        })),
+        "dep" => Arc::new(RwLock::new(DepartureRateLimit {
+            site,
+            slots,
+            reason: Some(reason),
+        })),
+        other => panic!("unknown disruption type: {}", other),
+    }
+}
+
+// MARK: SQLite backend
+
 pub struct SqliteScenarioLoader {
     conn: Connection,
     id: String,
@@ -43,6 +429,7 @@ pub enum ScenarioLoaderError {
     DatabaseError(rusqlite::Error),
     MissingRequiredDataError(&'static str),
     FormatError(ParseError),
+    IoError(std::io::Error),
 }
 
 impl From<rusqlite::Error> for ScenarioLoaderError {
@@ -55,35 +442,33 @@ impl From<ParseError> for ScenarioLoaderError {
         Self::FormatError(value)
     }
 }
-
-impl ScenarioLoader<ScenarioLoaderError> for SqliteScenarioLoader {
-    fn read_model(&self) -> Result<Model, ScenarioLoaderError> {
-        let (now, end, config) = self.read_config()?;
-        let (tx, rx) = mpsc::channel();
-        let mut model = Model {
-            airports: HashMap::new(),
-            fleet: HashMap::new(),
-            crew: HashMap::new(),
-            flights: HashMap::new(),
-            disruptions: DisruptionIndex::new(),
-            _now: Arc::new(RwLock::new(now)),
-            end,
-            publisher: tx,
-            metrics: RwLock::new(Some(MetricsProcessor::new(rx))),
-            config,
-        };
-        self.read_airports(&mut model)?;
-        self.read_aircraft(&mut model)?;
-        self.read_crew(&mut model)?;
-        self.read_flights(&mut model)?;
-        self.read_demand(&mut model)?;
-        self.read_disruptions(&mut model)?;
-        Ok(model)
+impl From<std::io::Error> for ScenarioLoaderError {
+    fn from(value: std::io::Error) -> Self {
+        Self::IoError(value)
     }
+}
 
-    fn read_dispatcher(&self, model: Arc<Model>) -> Result<Dispatcher, ScenarioLoaderError> {
+impl ScenarioSource for SqliteScenarioLoader {
+    type Error = ScenarioLoaderError;
+
+    fn config(&self) -> Result<ScenarioConfigRow, ScenarioLoaderError> {
         let mut stmt = self.conn.prepare(
-            "SELECT aircraft_selector, crew_selector, wait_for_deadheaders, aircraft_reassign_tolerance, crew_reassign_tolerance FROM scenarios WHERE sid = (?1)")?;
+            "SELECT start_time, end_time, crew_turnaround_time, aircraft_turnaround_time, max_delay,
+                    aircraft_selector, crew_selector, wait_for_deadheaders,
+                    aircraft_reassign_tolerance, crew_reassign_tolerance,
+                    aircraft_search_beam_width, aircraft_search_max_depth,
+                    crew_max_duty, crew_min_rest, aircraft_search_objective,
+                    aircraft_max_ferry_legs, aircraft_max_ferry_duration,
+                    assignment_window_violation_weight, assignment_deadhead_penalty,
+                    crew_duty_fdp_reduced_report_hours, crew_duty_fdp_base_report_hours,
+                    crew_duty_reduced_report_hour_start, crew_duty_reduced_report_hour_end,
+                    crew_duty_fdp_reduction_per_segment, crew_duty_fdp_floor,
+                    crew_duty_min_rest_floor, crew_duty_min_rest_scale,
+                    crew_duty_max_cumulative_flight_time, crew_duty_cumulative_window,
+                    batch_assign, batch_assign_cost, conflict_graph_enabled,
+                    resource_wait_base, resource_wait_cap, max_resource_wait,
+                    line_sink_path
+             FROM scenarios WHERE sid = (?1)")?;
         let mut rows = stmt.query([&self.id])?;
         let Some(row) = rows.next()? else {
             return Err(ScenarioLoaderError::MissingRequiredDataError(
@@ -91,274 +476,182 @@ impl ScenarioLoader<ScenarioLoaderError> for SqliteScenarioLoader {
             ));
         };
 
-        let asel: Option<String> = row.get("aircraft_selector")?;
-        let asel = asel.map(|asel| strategies::new_for_aircraft(&asel));
-        let csel: Option<String> = row.get("crew_selector")?;
-        let csel = csel.map(|csel| strategies::new_for_crew(&csel));
-
-        Ok(Dispatcher {
-            model,
-            aircraft_selector: asel,
-            crew_selector: csel,
+        Ok(ScenarioConfigRow {
+            start: Self::parse_time(&row.get::<&str, String>("start_time")?)?,
+            end: Self::parse_time(&row.get::<&str, String>("end_time")?)?,
+            crew_turnaround_time: TimeDelta::minutes(row.get("crew_turnaround_time")?),
+            aircraft_turnaround_time: TimeDelta::minutes(row.get("aircraft_turnaround_time")?),
+            max_delay: TimeDelta::minutes(row.get("max_delay")?),
+            aircraft_selector: row.get("aircraft_selector")?,
+            crew_selector: row.get("crew_selector")?,
             wait_for_deadheaders: row.get::<&str, i32>("wait_for_deadheaders")? > 0i32,
-            aircraft_tolerance_before_reassign: TimeDelta::minutes(
+            aircraft_reassign_tolerance: TimeDelta::minutes(
                 row.get("aircraft_reassign_tolerance")?,
             ),
-            use_fallback_aircraft_selector: true, // TODO add adjuster
-            crew_tolerance_before_reassign: TimeDelta::minutes(row.get("crew_reassign_tolerance")?),
-            update_queue: BinaryHeap::new(),
-            aircraft_reassigned: HashSet::new(),
+            crew_reassign_tolerance: TimeDelta::minutes(row.get("crew_reassign_tolerance")?),
+            aircraft_search_beam_width: row.get("aircraft_search_beam_width")?,
+            aircraft_search_max_depth: row.get("aircraft_search_max_depth")?,
+            crew_max_duty: TimeDelta::minutes(row.get("crew_max_duty")?),
+            crew_min_rest: TimeDelta::minutes(row.get("crew_min_rest")?),
+            aircraft_search_objective: row.get("aircraft_search_objective")?,
+            aircraft_max_ferry_legs: row.get("aircraft_max_ferry_legs")?,
+            aircraft_max_ferry_duration: TimeDelta::minutes(
+                row.get("aircraft_max_ferry_duration")?,
+            ),
+            assignment_window_violation_weight: row.get("assignment_window_violation_weight")?,
+            assignment_deadhead_penalty: row.get("assignment_deadhead_penalty")?,
+            crew_duty_engine: Far117LikeEngine {
+                fdp_reduced_report_hours: TimeDelta::minutes(
+                    row.get("crew_duty_fdp_reduced_report_hours")?,
+                ),
+                fdp_base_report_hours: TimeDelta::minutes(
+                    row.get("crew_duty_fdp_base_report_hours")?,
+                ),
+                reduced_report_hour_start: row.get("crew_duty_reduced_report_hour_start")?,
+                reduced_report_hour_end: row.get("crew_duty_reduced_report_hour_end")?,
+                fdp_reduction_per_segment: TimeDelta::minutes(
+                    row.get("crew_duty_fdp_reduction_per_segment")?,
+                ),
+                fdp_floor: TimeDelta::minutes(row.get("crew_duty_fdp_floor")?),
+                min_rest_floor: TimeDelta::minutes(row.get("crew_duty_min_rest_floor")?),
+                min_rest_scale: row.get("crew_duty_min_rest_scale")?,
+                max_cumulative_flight_time: TimeDelta::minutes(
+                    row.get("crew_duty_max_cumulative_flight_time")?,
+                ),
+                cumulative_window: TimeDelta::minutes(row.get("crew_duty_cumulative_window")?),
+            },
+            batch_assign: row.get::<&str, i32>("batch_assign")? > 0i32,
+            batch_assign_cost: row.get("batch_assign_cost")?,
+            conflict_graph_enabled: row.get::<&str, i32>("conflict_graph_enabled")? > 0i32,
+            resource_wait_base: TimeDelta::minutes(row.get("resource_wait_base")?),
+            resource_wait_cap: TimeDelta::minutes(row.get("resource_wait_cap")?),
+            max_resource_wait: TimeDelta::minutes(row.get("max_resource_wait")?),
+            line_sink_path: row.get("line_sink_path")?,
         })
     }
-}
 
-impl SqliteScenarioLoader {
-    const TIME_FORMAT: &'static str = "%Y-%m-%d %H:%M:%S";
-
-    fn read_airports(&self, model: &mut Model) -> Result<(), ScenarioLoaderError> {
+    fn airports(&self) -> Result<Vec<AirportRow>, ScenarioLoaderError> {
         let mut stmt = self.conn.prepare(
             "SELECT code, max_dep_per_hour, max_arr_per_hour FROM airports WHERE sid = (?1)",
         )?;
         let mut query = stmt.query([&self.id])?;
+        let mut out = Vec::new();
         while let Some(row) = query.next()? {
-            let code = AirportCode::from(&row.get("code")?);
-            model.airports.insert(
-                code,
-                Arc::new(RwLock::new(Airport {
-                    code,
-                    fleet: HashSet::new(),
-                    crew: HashSet::new(),
-                    passengers: Vec::new(),
-                    max_arr_per_hour: row.get("max_arr_per_hour")?,
-                    max_dep_per_hour: row.get("max_dep_per_hour")?,
-                    departure_count: (model.now(), 0),
-                    arrival_count: (model.now(), 0),
-                })),
-            );
+            out.push(AirportRow {
+                code: AirportCode::from(&row.get("code")?),
+                max_dep_per_hour: row.get("max_dep_per_hour")?,
+                max_arr_per_hour: row.get("max_arr_per_hour")?,
+            });
         }
-        Ok(())
+        Ok(out)
     }
 
-    fn read_aircraft(&self, model: &mut Model) -> Result<(), ScenarioLoaderError> {
+    fn aircraft(&self) -> Result<Vec<AircraftRow>, ScenarioLoaderError> {
         let mut stmt = self
             .conn
-            .prepare("SELECT tail, location, typename, capacity FROM aircraft WHERE sid = (?1)")?;
+            .prepare("SELECT tail, location, typename, capacity, cargo_capacity FROM aircraft WHERE sid = (?1)")?;
         let mut query = stmt.query([&self.id])?;
+        let mut out = Vec::new();
         while let Some(row) = query.next()? {
-            let tail: String = row.get("tail")?;
-            let location = AirportCode::from(&row.get("location")?);
-            model.fleet.insert(
-                tail.clone(),
-                Arc::new(RwLock::new(Aircraft::new(
-                    tail.clone(),
-                    location,
-                    &model.now(),
-                    row.get("typename")?,
-                    row.get("capacity")?,
-                ))),
-            );
-            model.airports[&location]
-                .write()
-                .unwrap()
-                .fleet
-                .insert(tail);
+            out.push(AircraftRow {
+                tail: row.get("tail")?,
+                location: AirportCode::from(&row.get("location")?),
+                typename: row.get("typename")?,
+                capacity: row.get("capacity")?,
+                cargo_capacity: row.get("cargo_capacity")?,
+            });
         }
-        Ok(())
+        Ok(out)
     }
 
-    fn read_crew(&self, model: &mut Model) -> Result<(), ScenarioLoaderError> {
+    fn crew(&self) -> Result<Vec<CrewRow>, ScenarioLoaderError> {
         let mut stmt = self
             .conn
             .prepare("SELECT id, location FROM crew WHERE sid = ?1")?;
         let mut rows = stmt.query([&self.id])?;
+        let mut out = Vec::new();
         while let Some(row) = rows.next()? {
-            let cid: CrewId = row.get("id")?;
-            let location = AirportCode::from(&row.get("location")?);
-            model.crew.insert(
-                cid,
-                Arc::new(RwLock::new(Crew::new(cid, location, model.now()))),
-            );
-            model.airports[&location].write().unwrap().crew.insert(cid);
+            out.push(CrewRow {
+                id: row.get("id")?,
+                location: AirportCode::from(&row.get("location")?),
+            });
         }
-        Ok(())
+        Ok(out)
     }
 
-    fn read_flights(&self, model: &mut Model) -> Result<(), ScenarioLoaderError> {
+    fn flights(&self) -> Result<Vec<FlightRow>, ScenarioLoaderError> {
         let mut stmt = self.conn.prepare(
             "SELECT id, flight_number, aircraft, origin, pilot, dest, sched_depart, sched_arrive FROM flights WHERE sid = ?1")?;
         let mut rows = stmt.query([&self.id])?;
+        let mut out = Vec::new();
         while let Some(row) = rows.next()? {
-            let mut crews_query = self
+            let flight_id: FlightId = row.get("id")?;
+            let mut deadheaders_stmt = self
                 .conn
                 .prepare_cached("SELECT id FROM deadheaders WHERE sid = ?1 AND fid = ?2")?;
-            let flight_id: FlightId = row.get("id")?;
-            let mut crews_rows = crews_query.query(rusqlite::params![&self.id, flight_id])?;
-            let mut flight = Flight {
+            let mut deadheader_rows =
+                deadheaders_stmt.query(rusqlite::params![&self.id, flight_id])?;
+            let mut deadheaders = Vec::new();
+            while let Some(deadheader_row) = deadheader_rows.next()? {
+                deadheaders.push(deadheader_row.get("id")?);
+            }
+            out.push(FlightRow {
                 id: flight_id,
                 flight_number: row.get("flight_number")?,
                 aircraft_tail: row.get("aircraft")?,
                 origin: AirportCode::from(&row.get("origin")?),
                 dest: AirportCode::from(&row.get("dest")?),
-                crew: {
-                    let pilot: Option<CrewId> = row.get("pilot")?;
-                    pilot.map(|i| vec![i]).unwrap_or(Vec::new())
-                },
-                passengers: Vec::new(),
-                cancelled: false,
-                depart_time: None,
-                arrive_time: None,
-                dep_delay: TimeDelta::zero(),
-                accum_delay: None,
+                pilot: row.get("pilot")?,
+                deadheaders,
                 sched_depart: Self::parse_time(&row.get::<&str, String>("sched_depart")?)?,
                 sched_arrive: Self::parse_time(&row.get::<&str, String>("sched_arrive")?)?,
-            };
-            while let Some(deadheader_row) = crews_rows.next()? {
-                flight.crew.push(deadheader_row.get("id")?);
-            }
-            model
-                .flights
-                .insert(flight_id, Arc::new(RwLock::new(flight)));
+            });
         }
-        Ok(())
-    }
-
-    fn read_config(
-        &self,
-    ) -> Result<(DateTime<Utc>, DateTime<Utc>, ModelConfig), ScenarioLoaderError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT start_time, end_time, crew_turnaround_time, aircraft_turnaround_time, max_delay FROM scenarios WHERE sid = (?1)")?;
-        let mut rows = stmt.query([&self.id])?;
-        let Some(row) = rows.next()? else {
-            return Err(ScenarioLoaderError::MissingRequiredDataError(
-                "Missing config info",
-            ));
-        };
-
-        let start_time_str: String = row.get("start_time")?;
-        let start = Self::parse_time(&start_time_str)?;
-        let end_time_str: String = row.get("end_time")?;
-        let end = Self::parse_time(&end_time_str)?;
-        Ok((
-            start,
-            end,
-            ModelConfig {
-                crew_turnaround_time: TimeDelta::minutes(row.get("crew_turnaround_time")?),
-                aircraft_turnaround_time: TimeDelta::minutes(row.get("aircraft_turnaround_time")?),
-                max_delay: TimeDelta::minutes(row.get("max_delay")?),
-            },
-        ))
+        Ok(out)
     }
 
-    fn read_demand(&self, model: &mut Model) -> Result<(), ScenarioLoaderError> {
+    fn demand(&self) -> Result<Vec<DemandRow>, ScenarioLoaderError> {
         let mut stmt = self
             .conn
-            .prepare("SELECT path, amount FROM demand WHERE sid = ?1")?;
+            .prepare("SELECT path, amount, bag_weight FROM demand WHERE sid = ?1")?;
         let mut rows = stmt.query([&self.id])?;
-
+        let mut out = Vec::new();
         while let Some(row) = rows.next()? {
             let path_str: String = row.get("path")?;
-            let demand = PassengerDemand {
+            out.push(DemandRow {
                 path: path_str
                     .split('-')
                     .map(|string| AirportCode::from(&string.to_owned()))
                     .collect(),
                 count: row.get("amount")?,
-                flights_taken: Vec::new(),
-            };
-            if demand.count > 0 {
-                model
-                    .airports
-                    .get(&demand.path[0])
-                    .unwrap()
-                    .write()
-                    .unwrap()
-                    .passengers
-                    .push(demand);
-            }
+                bag_weight: row.get("bag_weight")?,
+            });
         }
-        Ok(())
+        Ok(out)
     }
 
-    fn read_disruptions(&self, model: &mut Model) -> Result<(), ScenarioLoaderError> {
+    fn disruptions(&self) -> Result<Vec<DisruptionRow>, ScenarioLoaderError> {
         let mut stmt = self.conn.prepare(
             "SELECT airport, start, end, hourly_rate, type, reason FROM disruptions WHERE sid = ? ORDER BY airport, type, start ASC",
         )?;
         let mut rows = stmt.query([&self.id])?;
-
-        if let Some(first_row) = rows.next()? {
-            let mut ongoing_reason: String = first_row.get("reason")?;
-            let mut ongoing_site = AirportCode::from(&first_row.get("airport")?);
-            let mut ongoing_type: String = first_row.get("type")?;
-            let mut ongoing_start = Self::parse_time(&first_row.get::<&str, String>("start")?)?;
-            let mut ongoing_end = Self::parse_time(&first_row.get::<&str, String>("end")?)?;
-            let mut ongoing_rates: Vec<u32> = std::iter::repeat(first_row.get("hourly_rate")?)
-                .take((ongoing_end - ongoing_start).num_hours() as usize)
-                .collect();
-
-            while let Some(row) = rows.next()? {
-                let start = Self::parse_time(&row.get::<&str, String>("start")?)?;
-                let end = Self::parse_time(&row.get::<&str, String>("end")?)?;
-                let rate: u32 = row.get("hourly_rate")?;
-                let _type: String = row.get("type")?;
-                let site = AirportCode::from(&row.get("airport")?);
-
-                if site != ongoing_site || _type != ongoing_type || start != ongoing_end {
-                    // The ongoing CSSM is ready to be built
-                    println!("Disruption reading debug: read {:?} for {:?} (type = {})", ongoing_rates, ongoing_site, ongoing_type);
-                    let slot_man = CumulativeSmallSlotManager::<FlightId>::new(ongoing_start, ongoing_rates);
-                    println!("{:?}", slot_man.hourly_accumulation_limit);
-                    let disruption: Arc<RwLock<dyn Disruption>> = match ongoing_type.as_str() {
-                        "gdp" => Arc::new(RwLock::new(GroundDelayProgram {
-                            site: ongoing_site,
-                            slots: slot_man,
-                            reason: Some(ongoing_reason),
-                        })),
-                        "dep" => Arc::new(RwLock::new(DepartureRateLimit {
-                            site: ongoing_site,
-                            slots: slot_man,
-                            reason: Some(ongoing_reason),
-                        })),
-                        _ => {
-                            return Err(ScenarioLoaderError::MissingRequiredDataError(
-                                "unknown disruption type",
-                            ))
-                        }
-                    };
-                    model.disruptions.add_disruption(disruption);
-                    ongoing_rates = Vec::new();
-                    ongoing_site = site;
-                    ongoing_type = _type;
-                    ongoing_start = start;
-                    ongoing_reason = row.get("reason")?;
-                }
-                ongoing_end = end;
-                ongoing_rates.extend(std::iter::repeat(rate).take((end - start).num_hours() as usize));
-            }
-            // TODO fix duplication
-            println!("Disruption reading debug: read {:?} for {:?} (type = {})", ongoing_rates, ongoing_site, ongoing_type);
-            let slot_man = CumulativeSmallSlotManager::<FlightId>::new(ongoing_start, ongoing_rates);
-            println!("{:?}", slot_man.hourly_accumulation_limit);
-            let disruption: Arc<RwLock<dyn Disruption>> = match ongoing_type.as_str() {
-                "gdp" => Arc::new(RwLock::new(GroundDelayProgram {
-                    site: ongoing_site,
-                    slots: slot_man,
-                    reason: Some(ongoing_reason),
-                })),
-                "dep" => Arc::new(RwLock::new(DepartureRateLimit {
-                    site: ongoing_site,
-                    slots: slot_man,
-                    reason: Some(ongoing_reason),
-                })),
-                _ => {
-                    return Err(ScenarioLoaderError::MissingRequiredDataError(
-                        "unknown disruption type",
-                    ))
-                }
-            };
-            model.disruptions.add_disruption(disruption);
+        let mut out = Vec::new();
+        while let Some(row) = rows.next()? {
+            out.push(DisruptionRow {
+                site: AirportCode::from(&row.get("airport")?),
+                start: Self::parse_time(&row.get::<&str, String>("start")?)?,
+                end: Self::parse_time(&row.get::<&str, String>("end")?)?,
+                hourly_rate: row.get("hourly_rate")?,
+                kind: row.get("type")?,
+                reason: row.get("reason")?,
+            });
         }
-        Ok(())
+        Ok(out)
     }
+}
+
+impl SqliteScenarioLoader {
+    const TIME_FORMAT: &'static str = "%Y-%m-%d %H:%M:%S";
 
     fn parse_time(time: &str) -> Result<DateTime<Utc>, ScenarioLoaderError> {
         Ok(NaiveDateTime::parse_from_str(time, Self::TIME_FORMAT)?.and_utc())
@@ -0,0 +1,142 @@
+//! A slot-indexed clock anchored to a program's start ("genesis") and a fixed slot duration,
+//! factored out of the `TimeDelta` arithmetic that used to be inlined throughout
+//! `CumulativeSmallSlotManager` (and duplicated across its tests).
+//!
+//! Callers don't always have a clock perfectly synchronized with the program's own: a request
+//! can arrive a few milliseconds before `genesis`, or a hair past the end of the program's last
+//! slot. `tolerance` lets both cases clamp into the nearest valid slot instead of being rejected;
+//! anything further outside the program's window than `tolerance` still returns `None`.
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+pub type SlotIndex = usize;
+
+#[derive(Debug, Clone)]
+pub struct SlotClock {
+    genesis: DateTime<Utc>,
+    slot_duration: TimeDelta,
+    /// Total number of slots, if the program has a known end; `None` means unbounded.
+    num_slots: Option<usize>,
+    /// How far before `genesis`, or past the final slot's end, a timestamp can still be clamped
+    /// into range rather than rejected, absorbing clock skew between caller and program.
+    tolerance: TimeDelta,
+}
+
+impl SlotClock {
+    pub fn new(genesis: DateTime<Utc>, slot_duration: TimeDelta) -> Self {
+        Self {
+            genesis,
+            slot_duration,
+            num_slots: None,
+            tolerance: TimeDelta::zero(),
+        }
+    }
+
+    pub fn with_num_slots(mut self, num_slots: usize) -> Self {
+        self.num_slots = Some(num_slots);
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: TimeDelta) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The slot containing `now`. Clamps into slot 0 if `now` is before `genesis` but within
+    /// `tolerance`, and into the last slot if `now` is past the program's end but within
+    /// `tolerance` of it (only meaningful when `num_slots` is set). `None` if `now` falls further
+    /// outside the program's window than `tolerance` allows.
+    pub fn time_to_slot(&self, now: DateTime<Utc>) -> Option<SlotIndex> {
+        if now < self.genesis - self.tolerance {
+            return None;
+        }
+        let clamped = std::cmp::max(now, self.genesis);
+        let raw = ((clamped - self.genesis).num_nanoseconds()?
+            / self.slot_duration.num_nanoseconds()?)
+            .max(0) as usize;
+
+        match self.num_slots {
+            Some(n) if raw >= n => {
+                let program_end = self.genesis + self.slot_duration * n as i32;
+                if now < program_end + self.tolerance {
+                    Some(n - 1)
+                } else {
+                    None
+                }
+            }
+            _ => Some(raw),
+        }
+    }
+
+    /// The half-open `[start, end)` window covered by `slot`.
+    pub fn slot_to_window(&self, slot: SlotIndex) -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = self.genesis + self.slot_duration * slot as i32;
+        (start, start + self.slot_duration)
+    }
+
+    /// Number of whole slots touched by the half-open range `[a, b)`. Zero if the range is empty
+    /// or `a` falls outside the clock's (tolerance-widened) window.
+    pub fn slots_in_range(&self, a: DateTime<Utc>, b: DateTime<Utc>) -> usize {
+        if b <= a {
+            return 0;
+        }
+        let Some(start_slot) = self.time_to_slot(a) else {
+            return 0;
+        };
+        let end_slot = self
+            .time_to_slot(b - TimeDelta::nanoseconds(1))
+            .unwrap_or(start_slot);
+        end_slot.saturating_sub(start_slot) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_to_slot_within_window() {
+        let genesis = Utc::now();
+        let clock = SlotClock::new(genesis, TimeDelta::hours(1));
+        assert_eq!(clock.time_to_slot(genesis), Some(0));
+        assert_eq!(clock.time_to_slot(genesis + TimeDelta::minutes(90)), Some(1));
+    }
+
+    #[test]
+    fn tolerance_clamps_before_genesis_and_past_end() {
+        let genesis = Utc::now();
+        let clock = SlotClock::new(genesis, TimeDelta::hours(1))
+            .with_num_slots(3)
+            .with_tolerance(TimeDelta::minutes(5));
+
+        assert_eq!(clock.time_to_slot(genesis - TimeDelta::minutes(2)), Some(0));
+        assert_eq!(clock.time_to_slot(genesis - TimeDelta::minutes(10)), None);
+
+        let program_end = genesis + TimeDelta::hours(3);
+        assert_eq!(clock.time_to_slot(program_end + TimeDelta::minutes(2)), Some(2));
+        assert_eq!(clock.time_to_slot(program_end + TimeDelta::minutes(10)), None);
+    }
+
+    #[test]
+    fn slot_to_window_round_trips() {
+        let genesis = Utc::now();
+        let clock = SlotClock::new(genesis, TimeDelta::minutes(15));
+        assert_eq!(
+            clock.slot_to_window(2),
+            (genesis + TimeDelta::minutes(30), genesis + TimeDelta::minutes(45))
+        );
+    }
+
+    #[test]
+    fn slots_in_range_counts_boundary_aligned_and_partial() {
+        let genesis = Utc::now();
+        let clock = SlotClock::new(genesis, TimeDelta::hours(1));
+        // Exactly two full hours, boundary-aligned.
+        assert_eq!(clock.slots_in_range(genesis, genesis + TimeDelta::hours(2)), 2);
+        // A partial third hour still counts as touching a third slot.
+        assert_eq!(
+            clock.slots_in_range(genesis + TimeDelta::minutes(30), genesis + TimeDelta::hours(2) + TimeDelta::minutes(30)),
+            3
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! A duration-and-window slot reservation layer that solves across every outstanding request at
+//! once, rather than each `SlotManager`/`CumulativeSmallSlotManager` allocating independently
+//! and greedily the way `GroundDelayProgram`/`DepartureRateLimit` do today.
+//!
+//! A `SlotRequest` asks for `duration` of occupancy anywhere in `[earliest, latest]`, discretized
+//! into `granularity`-sized ticks. A `SlotSolver` assigns requests to start ticks, letting at most
+//! `capacity_at(tick)` requests start at each tick, and simply omits anything it
+//! couldn't place — callers should treat a missing item the same as `Clearance::Deferred`.
+//! Neither solver here uses `duration`: a request counts against capacity at its start tick
+//! only, not at the later ticks its duration would cover.
+//! `SlotPool` holds the outstanding requests so new ones can be added and voided ones removed
+//! without callers re-threading the whole request list themselves; `resolve` re-runs the chosen
+//! solver from scratch over whatever is currently pending.
+
+use std::collections::{BinaryHeap, HashMap};
+use std::cmp::{Ordering, Reverse};
+use std::hash::Hash;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// One flight's ask: `duration` of runway/gate occupancy sometime within `[earliest, latest]`.
+#[derive(Debug, Clone)]
+pub struct SlotRequest<T> {
+    pub item: T,
+    pub earliest: DateTime<Utc>,
+    pub latest: DateTime<Utc>,
+    pub duration: TimeDelta,
+}
+
+/// Start time assigned to each request that could be placed; anything genuinely infeasible
+/// within its window at the given capacity is simply absent.
+pub type SlotAssignment<T> = HashMap<T, DateTime<Utc>>;
+
+/// A back end that assigns `SlotRequest`s to discrete start times, `granularity` apart, subject
+/// to at most `capacity_at(tick)` requests starting at each tick.
+pub trait SlotSolver<T: Clone + Eq + Hash> {
+    fn solve(
+        &self,
+        requests: &[SlotRequest<T>],
+        granularity: TimeDelta,
+        capacity_at: &dyn Fn(DateTime<Utc>) -> usize,
+    ) -> SlotAssignment<T>;
+}
+
+/// First-fit in request order: each request claims the earliest open tick in its window,
+/// matching today's `SlotManager`/`CumulativeSmallSlotManager` behavior. Fast, but a request
+/// near the front of the list can take a tick a later, tighter-windowed request needed.
+pub struct GreedyFirstFit;
+
+impl<T: Clone + Eq + Hash> SlotSolver<T> for GreedyFirstFit {
+    fn solve(
+        &self,
+        requests: &[SlotRequest<T>],
+        granularity: TimeDelta,
+        capacity_at: &dyn Fn(DateTime<Utc>) -> usize,
+    ) -> SlotAssignment<T> {
+        let mut used: HashMap<DateTime<Utc>, usize> = HashMap::new();
+        let mut assignment = SlotAssignment::new();
+        for request in requests {
+            let mut tick = request.earliest;
+            while tick <= request.latest {
+                let occupied = used.get(&tick).copied().unwrap_or(0);
+                if occupied < capacity_at(tick) {
+                    used.insert(tick, occupied + 1);
+                    assignment.insert(request.item.clone(), tick);
+                    break;
+                }
+                tick += granularity;
+            }
+        }
+        assignment
+    }
+}
+
+/// Earliest-deadline-first: at each tick (in chronological order), among requests already
+/// released (`earliest <= tick`) and not yet placed, the tightest-deadline (`latest`) ones claim
+/// that tick's capacity first. This is the classic optimal discipline for maximizing how many
+/// requests meet their deadline under per-tick capacity; as a tiebreak among equal deadlines it
+/// favors the earliest-released request, which tends to minimize total delay in practice. It is
+/// not an exact minimum-total-delay solve (that's an NP-hard scheduling problem in general), but
+/// it strictly dominates `GreedyFirstFit` on feasibility and is what "optimal" means here absent
+/// pulling in an ILP/SAT dependency.
+pub struct EarliestDeadlineFirst;
+
+impl<T: Clone + Eq + Hash> SlotSolver<T> for EarliestDeadlineFirst {
+    fn solve(
+        &self,
+        requests: &[SlotRequest<T>],
+        granularity: TimeDelta,
+        capacity_at: &dyn Fn(DateTime<Utc>) -> usize,
+    ) -> SlotAssignment<T> {
+        if requests.is_empty() {
+            return SlotAssignment::new();
+        }
+        let mut by_release: Vec<&SlotRequest<T>> = requests.iter().collect();
+        by_release.sort_by_key(|r| r.earliest);
+
+        let first_tick = by_release[0].earliest;
+        let last_tick = requests.iter().map(|r| r.latest).max().unwrap();
+
+        let mut assignment = SlotAssignment::new();
+        let mut ready: BinaryHeap<Reverse<DeadlineOrdered<T>>> = BinaryHeap::new();
+        let mut next_release = 0usize;
+
+        let mut tick = first_tick;
+        while tick <= last_tick {
+            while next_release < by_release.len() && by_release[next_release].earliest <= tick {
+                ready.push(Reverse(DeadlineOrdered(by_release[next_release])));
+                next_release += 1;
+            }
+
+            let mut remaining_capacity = capacity_at(tick);
+            while remaining_capacity > 0 {
+                let Some(Reverse(DeadlineOrdered(request))) = ready.pop() else {
+                    break;
+                };
+                if request.latest < tick {
+                    // Missed its deadline before ever reaching a free tick; drop it.
+                    continue;
+                }
+                assignment.insert(request.item.clone(), tick);
+                remaining_capacity -= 1;
+            }
+            tick += granularity;
+        }
+        assignment
+    }
+}
+
+/// Wraps a `SlotRequest` with an ordering keyed on `latest` (tightest deadline first), so it can
+/// live in a `BinaryHeap` used as a min-heap via `Reverse`.
+struct DeadlineOrdered<'a, T>(&'a SlotRequest<T>);
+
+impl<T> PartialEq for DeadlineOrdered<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.latest == other.0.latest
+    }
+}
+impl<T> Eq for DeadlineOrdered<'_, T> {}
+impl<T> PartialOrd for DeadlineOrdered<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T> Ord for DeadlineOrdered<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.latest.cmp(&other.0.latest)
+    }
+}
+
+/// The outstanding, not-yet-cleared slot requests across every active disruption at a site, plus
+/// the chosen solver. `resolve` re-runs the solver over whatever is currently pending; this is a
+/// full re-solve rather than an incremental patch, so it scales with the number of outstanding
+/// requests rather than the number of changes, but it keeps the solver implementations simple
+/// and correct by construction.
+pub struct SlotPool<T: Clone + Eq + Hash> {
+    granularity: TimeDelta,
+    solver: Box<dyn SlotSolver<T>>,
+    pending: Vec<SlotRequest<T>>,
+    assignment: SlotAssignment<T>,
+}
+
+impl<T: Clone + Eq + Hash> SlotPool<T> {
+    pub fn new(granularity: TimeDelta, solver: Box<dyn SlotSolver<T>>) -> Self {
+        Self {
+            granularity,
+            solver,
+            pending: Vec::new(),
+            assignment: SlotAssignment::new(),
+        }
+    }
+
+    /// Add or replace this item's outstanding request (a new flight appearing, or one whose
+    /// window changed).
+    pub fn request(&mut self, request: SlotRequest<T>) {
+        self.pending.retain(|r| r.item != request.item);
+        self.pending.push(request);
+    }
+
+    /// Withdraw an item's request, e.g. when its clearance is voided.
+    pub fn void(&mut self, item: &T) {
+        self.pending.retain(|r| r.item != *item);
+        self.assignment.remove(item);
+    }
+
+    /// Re-run the solver over the currently pending requests and return the new assignment.
+    pub fn resolve(&mut self, capacity_at: &dyn Fn(DateTime<Utc>) -> usize) -> &SlotAssignment<T> {
+        self.assignment = self.solver.solve(&self.pending, self.granularity, capacity_at);
+        &self.assignment
+    }
+
+    pub fn assigned(&self, item: &T) -> Option<DateTime<Utc>> {
+        self.assignment.get(item).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hours(n: i64) -> TimeDelta {
+        TimeDelta::hours(n)
+    }
+
+    #[test]
+    fn greedy_first_fit_respects_capacity_and_window() {
+        let origin = Utc::now();
+        let requests = vec![
+            SlotRequest { item: "a", earliest: origin, latest: origin + hours(2), duration: hours(1) },
+            SlotRequest { item: "b", earliest: origin, latest: origin + hours(2), duration: hours(1) },
+            SlotRequest { item: "c", earliest: origin, latest: origin + hours(2), duration: hours(1) },
+        ];
+        let assignment = GreedyFirstFit.solve(&requests, hours(1), &|_| 2);
+        assert_eq!(assignment["a"], origin);
+        assert_eq!(assignment["b"], origin);
+        assert_eq!(assignment["c"], origin + hours(1));
+    }
+
+    #[test]
+    fn greedy_first_fit_drops_infeasible_request() {
+        let origin = Utc::now();
+        let requests = vec![
+            SlotRequest { item: "a", earliest: origin, latest: origin, duration: hours(1) },
+            SlotRequest { item: "b", earliest: origin, latest: origin, duration: hours(1) },
+        ];
+        let assignment = GreedyFirstFit.solve(&requests, hours(1), &|_| 1);
+        assert_eq!(assignment.len(), 1);
+        assert!(assignment.contains_key("a"));
+        assert!(!assignment.contains_key("b"));
+    }
+
+    #[test]
+    fn earliest_deadline_first_prefers_tighter_window_under_contention() {
+        let origin = Utc::now();
+        // "tight" can only use the first tick; "loose" could use either. A first-fit-by-request-
+        // order solver that processed "loose" first would strand "tight".
+        let requests = vec![
+            SlotRequest { item: "loose", earliest: origin, latest: origin + hours(1), duration: hours(1) },
+            SlotRequest { item: "tight", earliest: origin, latest: origin, duration: hours(1) },
+        ];
+        let assignment = EarliestDeadlineFirst.solve(&requests, hours(1), &|_| 1);
+        assert_eq!(assignment["tight"], origin);
+        assert_eq!(assignment["loose"], origin + hours(1));
+    }
+
+    #[test]
+    fn slot_pool_resolves_after_request_and_void() {
+        let origin = Utc::now();
+        let mut pool = SlotPool::new(hours(1), Box::new(GreedyFirstFit));
+        pool.request(SlotRequest { item: 1u64, earliest: origin, latest: origin, duration: hours(1) });
+        pool.request(SlotRequest { item: 2u64, earliest: origin, latest: origin, duration: hours(1) });
+        pool.resolve(&|_| 1);
+        assert_eq!(pool.assigned(&1), Some(origin));
+        assert_eq!(pool.assigned(&2), None);
+
+        pool.void(&1);
+        pool.resolve(&|_| 1);
+        assert_eq!(pool.assigned(&1), None);
+        assert_eq!(pool.assigned(&2), Some(origin));
+    }
+}
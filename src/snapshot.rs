@@ -0,0 +1,226 @@
+//! Checkpoint/resume of a full `Model`, so a long-running simulation (or one about to apply a
+//! risky batch of disruptions) can be written to disk and later restored without replaying the
+//! scenario from scratch.
+//!
+//! `Disruption` trait objects can't be (de)serialized generically, so they go through
+//! `DisruptionRecord` (see `airport.rs`), which drops live slot-occupancy state. `restore`
+//! rebuilds that state by replaying `request_departure`/`request_arrival` for every flight that
+//! has not yet arrived.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::{BufReader, BufWriter},
+    sync::{mpsc, Arc, RwLock},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    aircraft::{Aircraft, Flight, FlightId},
+    airport::{Airport, AirportCode, DisruptionIndex, DisruptionRecord},
+    crew::{Crew, CrewId},
+    grpc::EventHub,
+    metrics::MetricsProcessor,
+    model::{Model, ModelConfig},
+};
+
+/// Bumped whenever `ModelSnapshot`'s shape changes in a way that breaks older files.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    Encode(bincode::Error),
+    VersionMismatch { found: u32, expected: u32 },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "snapshot I/O error: {}", err),
+            Self::Encode(err) => write!(f, "snapshot encoding error: {}", err),
+            Self::VersionMismatch { found, expected } => write!(
+                f,
+                "snapshot version {} is not supported (expected {})",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(value: bincode::Error) -> Self {
+        Self::Encode(value)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ModelSnapshot {
+    version: u32,
+    now: DateTime<Utc>,
+    end: DateTime<Utc>,
+    config: ModelConfig,
+    fleet: HashMap<String, Aircraft>,
+    crew: HashMap<CrewId, Crew>,
+    airports: HashMap<AirportCode, Airport>,
+    flights: HashMap<FlightId, Flight>,
+    disruptions: Vec<DisruptionRecord>,
+}
+
+impl Model {
+    /// Write this model's full state to `path`. Reads every entity under its own lock just
+    /// long enough to clone it, in ascending key order, so a concurrent run never has to wait
+    /// on more than one lock at a time.
+    pub fn snapshot(&self, path: &str) -> Result<(), SnapshotError> {
+        let mut tails: Vec<&String> = self.fleet.keys().collect();
+        tails.sort();
+        let fleet = tails
+            .into_iter()
+            .map(|tail| (tail.clone(), self.fleet[tail].read().unwrap().clone()))
+            .collect();
+
+        let mut crew_ids: Vec<&CrewId> = self.crew.keys().collect();
+        crew_ids.sort();
+        let crew = crew_ids
+            .into_iter()
+            .map(|id| (*id, self.crew[id].read().unwrap().clone()))
+            .collect();
+
+        let mut airport_codes: Vec<&AirportCode> = self.airports.keys().collect();
+        airport_codes.sort_by_key(|code| code.to_string());
+        let airports = airport_codes
+            .into_iter()
+            .map(|code| (*code, self.airports[code].read().unwrap().clone()))
+            .collect();
+
+        let mut flight_ids: Vec<&FlightId> = self.flights.keys().collect();
+        flight_ids.sort();
+        let flights = flight_ids
+            .into_iter()
+            .map(|id| (*id, self.flights[id].read().unwrap().clone()))
+            .collect();
+
+        let disruptions = self
+            .disruptions
+            .iter()
+            .map(|disruption| disruption.read().unwrap().snapshot())
+            .collect();
+
+        let snapshot = ModelSnapshot {
+            version: SNAPSHOT_VERSION,
+            now: self.now(),
+            end: self.end,
+            config: self.config.clone(),
+            fleet,
+            crew,
+            airports,
+            flights,
+            disruptions,
+        };
+
+        let file = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(file, &snapshot)?;
+        Ok(())
+    }
+
+    /// Restore a model from a file written by `snapshot`. Sets up its own event channels the
+    /// same way `ScenarioLoader::read_model` does, since there is no longer a scenario loader
+    /// around to do it — for the same reason this takes no `publisher` argument: every other
+    /// `Model`-constructing path (`read_model`, this one) owns building its own channels rather
+    /// than accepting one from the caller, and a restored model shouldn't be the one exception.
+    /// `line_sink_path`, unlike the publisher, is just a config value rather than a channel, so
+    /// it's taken from the caller the same way `assemble_model` takes it from `ScenarioConfigRow`
+    /// (see `MetricsProcessor::line_sink`).
+    pub fn restore(path: &str, line_sink_path: Option<&str>) -> Result<Model, SnapshotError> {
+        let file = BufReader::new(File::open(path)?);
+        let snapshot: ModelSnapshot = bincode::deserialize_from(file)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::VersionMismatch {
+                found: snapshot.version,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+
+        let (tx, hub_rx) = mpsc::channel();
+        let (metrics_tx, metrics_rx) = mpsc::channel();
+        let (_hub_handle, hub) = EventHub::spawn(hub_rx, metrics_tx);
+        let line_sink: Option<Box<dyn std::io::Write + Send>> = match line_sink_path {
+            Some(path) => Some(Box::new(
+                std::fs::OpenOptions::new().create(true).append(true).open(path)?,
+            )),
+            None => None,
+        };
+
+        let mut disruptions = DisruptionIndex::new();
+        for record in snapshot.disruptions {
+            disruptions.add_disruption(record.restore());
+        }
+
+        let model = Model {
+            airports: snapshot
+                .airports
+                .into_iter()
+                .map(|(code, airport)| (code, Arc::new(RwLock::new(airport))))
+                .collect(),
+            fleet: snapshot
+                .fleet
+                .into_iter()
+                .map(|(tail, aircraft)| (tail, Arc::new(RwLock::new(aircraft))))
+                .collect(),
+            crew: snapshot
+                .crew
+                .into_iter()
+                .map(|(id, crew)| (id, Arc::new(RwLock::new(crew))))
+                .collect(),
+            flights: snapshot
+                .flights
+                .into_iter()
+                .map(|(id, flight)| (id, Arc::new(RwLock::new(flight))))
+                .collect(),
+            disruptions,
+            _now: Arc::new(RwLock::new(snapshot.now)),
+            end: snapshot.end,
+            publisher: tx,
+            event_broadcast: hub.broadcast,
+            metrics: RwLock::new(Some(MetricsProcessor::new(metrics_rx, line_sink))),
+            config: snapshot.config,
+        };
+
+        model.reslot_outstanding_flights();
+        Ok(model)
+    }
+
+    /// Snapshots intentionally drop which flight is occupying which disruption slot (that's
+    /// solver state, not ground truth). After `restore`, replay clearance requests for every
+    /// flight that hasn't arrived yet so each `Disruption`'s slots are occupied again exactly
+    /// as `reserve_earliest` would have left them.
+    fn reslot_outstanding_flights(&self) {
+        let mut flight_ids: Vec<FlightId> = self.flights.keys().copied().collect();
+        flight_ids.sort();
+        for flight_id in flight_ids {
+            let (cancelled, departed, arrived) = {
+                let flight = self.flight_read(flight_id);
+                (flight.cancelled, flight.took_off(), flight.arrive_time.is_some())
+            };
+            if cancelled || arrived {
+                continue;
+            }
+            if !departed {
+                self.request_departure(flight_id);
+            } else {
+                self.request_arrival(flight_id);
+            }
+        }
+    }
+}
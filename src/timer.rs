@@ -0,0 +1,130 @@
+//! A fixed-capacity timer wheel for driving disruption re-polling.
+//!
+//! `Disruption::request_depart`/`request_arrive` return a `Clearance::EDCT`/`Deferred` time at
+//! which the dispatcher should ask again, but nothing indexes those due times directly; today
+//! the dispatcher just re-enqueues the flight into its own `BinaryHeap`. `ClearanceTimer<T>`
+//! is the reusable structure for that pattern: O(1) amortized insert via direct bucket
+//! indexing, and O(log N) (per-bucket binary search) lookup of what's due.
+//!
+//! Items are placed into `((t - origin) / granularity) % capacity`-th bucket, each kept sorted
+//! by time. The wheel cannot represent a time more than `granularity * capacity` past `origin`;
+//! `add` rejects (returns `false` for) anything earlier than `origin` or beyond that horizon
+//! rather than silently wrapping it onto the wrong bucket.
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+#[derive(Debug)]
+pub struct ClearanceTimer<T> {
+    origin: DateTime<Utc>,
+    granularity: TimeDelta,
+    capacity: usize,
+    buckets: Vec<Vec<(DateTime<Utc>, T)>>,
+    /// Index of the bucket holding the wheel's current position, advanced by `take_until`.
+    cursor: usize,
+}
+
+impl<T> ClearanceTimer<T> {
+    pub fn new(origin: DateTime<Utc>, granularity: TimeDelta, capacity: usize) -> Self {
+        Self {
+            origin,
+            granularity,
+            capacity,
+            buckets: std::iter::repeat_with(Vec::new).take(capacity).collect(),
+            cursor: 0,
+        }
+    }
+
+    /// Schedule `item` to become due at `time`. Returns `false` without inserting if `time` is
+    /// before `origin` or falls beyond the wheel's horizon.
+    pub fn add(&mut self, time: DateTime<Utc>, item: T) -> bool {
+        let Some(index) = self.bucket_index(&time) else {
+            return false;
+        };
+        let bucket = &mut self.buckets[index];
+        let pos = bucket.partition_point(|(t, _)| *t <= time);
+        bucket.insert(pos, (time, item));
+        true
+    }
+
+    /// The earliest due time scheduled anywhere in the wheel, found by scanning forward from
+    /// the cursor for the first non-empty bucket (each bucket's first entry is its earliest).
+    pub fn next_time(&self) -> Option<DateTime<Utc>> {
+        (0..self.capacity)
+            .map(|offset| (self.cursor + offset) % self.capacity)
+            .find_map(|index| self.buckets[index].first().map(|(t, _)| *t))
+    }
+
+    /// Drain and return every item due at or before `now`, across all buckets, and advance the
+    /// cursor to `now`'s bucket.
+    pub fn take_until(&mut self, now: DateTime<Utc>) -> Vec<T> {
+        if let Some(index) = self.bucket_index(&now) {
+            self.cursor = index;
+        }
+        let mut due = Vec::new();
+        for bucket in &mut self.buckets {
+            let pos = bucket.partition_point(|(t, _)| *t <= now);
+            due.extend(bucket.drain(0..pos).map(|(_, item)| item));
+        }
+        due
+    }
+
+    fn bucket_index(&self, time: &DateTime<Utc>) -> Option<usize> {
+        if *time < self.origin {
+            return None;
+        }
+        let ticks = (*time - self.origin).num_nanoseconds()? / self.granularity.num_nanoseconds()?;
+        if ticks as usize >= self.capacity {
+            None
+        } else {
+            Some(ticks as usize)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_take_in_order() {
+        let origin = Utc::now();
+        let mut timer = ClearanceTimer::new(origin, TimeDelta::minutes(1), 60);
+        assert!(timer.add(origin + TimeDelta::minutes(5), "late"));
+        assert!(timer.add(origin + TimeDelta::minutes(2), "early"));
+
+        assert_eq!(timer.next_time(), Some(origin + TimeDelta::minutes(2)));
+        assert!(timer.take_until(origin + TimeDelta::minutes(1)).is_empty());
+        assert_eq!(
+            timer.take_until(origin + TimeDelta::minutes(2)),
+            vec!["early"]
+        );
+        assert_eq!(
+            timer.take_until(origin + TimeDelta::minutes(5)),
+            vec!["late"]
+        );
+        assert_eq!(timer.next_time(), None);
+    }
+
+    #[test]
+    fn rejects_before_origin_and_beyond_horizon() {
+        let origin = Utc::now();
+        let mut timer = ClearanceTimer::new(origin, TimeDelta::minutes(1), 10);
+        assert!(!timer.add(origin - TimeDelta::minutes(1), 1));
+        assert!(!timer.add(origin + TimeDelta::minutes(10), 2));
+        assert!(timer.add(origin + TimeDelta::minutes(9), 3));
+    }
+
+    #[test]
+    fn same_bucket_kept_sorted() {
+        let origin = Utc::now();
+        let mut timer = ClearanceTimer::new(origin, TimeDelta::minutes(10), 6);
+        timer.add(origin + TimeDelta::minutes(8), "c");
+        timer.add(origin + TimeDelta::minutes(1), "a");
+        timer.add(origin + TimeDelta::minutes(4), "b");
+
+        assert_eq!(
+            timer.take_until(origin + TimeDelta::minutes(9)),
+            vec!["a", "b", "c"]
+        );
+    }
+}